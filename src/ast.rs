@@ -4,13 +4,28 @@ use crate::error::Location;
 pub enum Type<'a> {
 	Number,
 	Boolean,
+	String,
 	Void,
-	Class(&'a str),
+	// a class or an interface with its type arguments
+	Class(&'a str, Vec<Type<'a>>),
+	// a type parameter of the enclosing generic function or class
+	#[allow(clippy::enum_variant_names)]
+	TypeParameter(&'a str),
+	Nullable(Box<Type<'a>>),
+	// the type of the null literal
+	Null,
+	// the argument types and the return type of a function
+	Function(Vec<Type<'a>>, Box<Type<'a>>),
+	// the element type of an array
+	Array(Box<Type<'a>>),
+	// the built-in Map with its key type and value type
+	Map(Box<Type<'a>>, Box<Type<'a>>),
 }
 
 pub struct Program<'a> {
 	pub functions: Vec<Function<'a>>,
 	pub classes: Vec<Class<'a>>,
+	pub interfaces: Vec<Interface<'a>>,
 	pub locations: std::collections::HashMap<* const Expression<'a>, Location>,
 }
 
@@ -19,6 +34,7 @@ impl <'a> Program<'a> {
 		Program {
 			functions: Vec::new(),
 			classes: Vec::new(),
+			interfaces: Vec::new(),
 			locations: std::collections::HashMap::new(),
 		}
 	}
@@ -41,23 +57,50 @@ impl <'a> Program<'a> {
 		}
 		None
 	}
+	pub fn get_interface(&self, name: &str) -> Option<&Interface<'a>> {
+		self.interfaces.iter().find(|interface| interface.name == name)
+	}
+}
+
+pub struct TypeParameter<'a> {
+	pub name: &'a str,
+	pub bound: Option<Type<'a>>,
 }
 
 pub struct Function<'a> {
 	pub name: &'a str,
+	pub type_parameters: Vec<TypeParameter<'a>>,
 	pub arguments: Vec<(&'a str, Type<'a>)>,
 	pub return_type: Type<'a>,
 	pub statements: Vec<Statement<'a>>,
+	pub location: Location,
+	// abstract methods have no statements
+	pub is_abstract: bool,
 }
 
 pub struct Class<'a> {
 	pub name: &'a str,
-	pub fields: Vec<(&'a str, Type<'a>)>,
+	pub type_parameters: Vec<TypeParameter<'a>>,
+	pub parent: Option<Type<'a>>,
+	pub interfaces: Vec<&'a str>,
+	pub is_abstract: bool,
+	pub fields: Vec<Field<'a>>,
 	pub methods: Vec<Function<'a>>,
+	pub location: Location,
 }
 
 impl <'a> Class<'a> {
-	pub fn get_method(&self, name: &str) -> Option<&Function<'a>> {
+	pub fn get_parent<'b>(&self, program: &'b Program<'a>) -> Option<&'b Class<'a>> {
+		match &self.parent {
+			Some(Type::Class(parent, _)) => program.get_class(parent),
+			_ => None,
+		}
+	}
+	// the class itself followed by its parent, the parent's parent and so on
+	pub fn ancestors<'b>(&'b self, program: &'b Program<'a>) -> impl Iterator<Item=&'b Class<'a>> {
+		std::iter::successors(Some(self), move |class| class.get_parent(program))
+	}
+	pub fn get_own_method(&self, name: &str) -> Option<&Function<'a>> {
 		for method in &self.methods {
 			if method.name == name {
 				return Some(method);
@@ -65,27 +108,68 @@ impl <'a> Class<'a> {
 		}
 		None
 	}
-	pub fn get_constructor(&self) -> Option<&Function<'a>> {
-		self.get_method("constructor")
+	pub fn get_method<'b>(&'b self, program: &'b Program<'a>, name: &str) -> Option<&'b Function<'a>> {
+		self.ancestors(program).find_map(|class| class.get_own_method(name))
 	}
-	pub fn get_field(&self, name: &str) -> Option<Type<'a>> {
-		for (field_name, field_type) in &self.fields {
-			if field_name == &name {
-				return Some(field_type.clone());
-			}
-		}
-		None
+	pub fn get_own_field(&self, name: &str) -> Option<&Field<'a>> {
+		self.fields.iter().find(|field| field.name == name)
 	}
 }
 
+pub struct Interface<'a> {
+	pub name: &'a str,
+	pub fields: Vec<Field<'a>>,
+	// method signatures, they are always abstract
+	pub methods: Vec<Function<'a>>,
+	pub location: Location,
+}
+
+impl <'a> Interface<'a> {
+	pub fn get_method(&self, name: &str) -> Option<&Function<'a>> {
+		self.methods.iter().find(|method| method.name == name)
+	}
+	pub fn get_field(&self, name: &str) -> Option<&Field<'a>> {
+		self.fields.iter().find(|field| field.name == name)
+	}
+}
+
+pub struct Field<'a> {
+	pub name: &'a str,
+	pub ty: Type<'a>,
+	pub initializer: Option<Box<Expression<'a>>>,
+	pub readonly: bool,
+}
+
 pub enum Statement<'a> {
 	VariableDeclaration {
 		name: &'a str,
-		expression: Box<Expression<'a>>,
+		ty: Option<Type<'a>>,
+		expression: Option<Box<Expression<'a>>>,
+		constant: bool,
+		location: Location,
 	},
 	If(If<'a>),
 	While(While<'a>),
-	Return(Box<Expression<'a>>),
+	DoWhile(DoWhile<'a>),
+	For(For<'a>),
+	ForOf(ForOf<'a>),
+	Break {
+		label: Option<&'a str>,
+		location: Location,
+	},
+	Continue {
+		label: Option<&'a str>,
+		location: Location,
+	},
+	Labeled {
+		label: &'a str,
+		statement: Box<Statement<'a>>,
+		location: Location,
+	},
+	Return {
+		expression: Option<Box<Expression<'a>>>,
+		location: Location,
+	},
 	Expression(Box<Expression<'a>>),
 	Block(Vec<Statement<'a>>),
 }
@@ -94,30 +178,85 @@ pub struct If<'a> {
 	pub condition: Box<Expression<'a>>,
 	pub statement: Box<Statement<'a>>,
 	pub else_statement: Option<Box<Statement<'a>>>,
+	pub location: Location,
 }
 
 pub struct While<'a> {
 	pub condition: Box<Expression<'a>>,
 	pub statement: Box<Statement<'a>>,
+	pub location: Location,
+}
+
+pub struct DoWhile<'a> {
+	pub statement: Box<Statement<'a>>,
+	pub condition: Box<Expression<'a>>,
+	pub location: Location,
+}
+
+pub struct For<'a> {
+	pub initializer: Option<Box<Statement<'a>>>,
+	pub condition: Option<Box<Expression<'a>>>,
+	pub step: Option<Box<Expression<'a>>>,
+	pub statement: Box<Statement<'a>>,
+	pub location: Location,
+}
+
+pub struct ForOf<'a> {
+	pub name: &'a str,
+	pub constant: bool,
+	pub iterable: Iterable<'a>,
+	pub statement: Box<Statement<'a>>,
+	pub location: Location,
+}
+
+pub enum Iterable<'a> {
+	Array(Box<Expression<'a>>),
+	// the numbers from start up to but not including end
+	Range {
+		start: Box<Expression<'a>>,
+		end: Box<Expression<'a>>,
+	},
 }
 
 pub enum Expression<'a> {
-	Number(&'a str),
+	Number(f64),
+	Boolean(bool),
+	String(String),
+	Null,
 	Name(&'a str),
 	ArithmeticExpression(ArithmeticExpression<'a>),
 	RelationalExpression(RelationalExpression<'a>),
 	LogicalExpression(LogicalExpression<'a>),
 	Not(Box<Expression<'a>>),
+	Minus(Box<Expression<'a>>),
+	Plus(Box<Expression<'a>>),
 	Assign {
 		name: Box<Expression<'a>>,
 		expression: Box<Expression<'a>>,
 	},
+	CompoundAssign {
+		operation: AssignOperation,
+		name: Box<Expression<'a>>,
+		expression: Box<Expression<'a>>,
+	},
+	PrefixIncrement(Box<Expression<'a>>),
+	PrefixDecrement(Box<Expression<'a>>),
+	PostfixIncrement(Box<Expression<'a>>),
+	PostfixDecrement(Box<Expression<'a>>),
+	Conditional {
+		condition: Box<Expression<'a>>,
+		then_expression: Box<Expression<'a>>,
+		else_expression: Box<Expression<'a>>,
+	},
 	Call {
 		function: Box<Expression<'a>>,
+		// explicit type arguments of a call to a generic function
+		type_arguments: Vec<Type<'a>>,
 		arguments: Vec<Box<Expression<'a>>>,
 	},
 	ClassInstantiation {
 		class: &'a str,
+		type_arguments: Vec<Type<'a>>,
 		arguments: Vec<Box<Expression<'a>>>,
 	},
 	PropertyAccess {
@@ -130,6 +269,25 @@ pub enum Expression<'a> {
 		arguments: Vec<Box<Expression<'a>>>,
 	},
 	This,
+	Array(Vec<Box<Expression<'a>>>),
+	Index {
+		array: Box<Expression<'a>>,
+		index: Box<Expression<'a>>,
+	},
+	Lambda {
+		arguments: Vec<(&'a str, Type<'a>)>,
+		// can be omitted if the body is a single expression
+		return_type: Option<Type<'a>>,
+		// either a block or, if the body is a single expression, a return statement
+		body: Box<Statement<'a>>,
+	},
+	SuperCall {
+		arguments: Vec<Box<Expression<'a>>>,
+	},
+	SuperMethodCall {
+		method: &'a str,
+		arguments: Vec<Box<Expression<'a>>>,
+	},
 }
 
 pub struct ArithmeticExpression<'a> {
@@ -172,6 +330,16 @@ pub enum LogicalOperation {
 	Or,
 }
 
+pub enum AssignOperation {
+	Add,
+	Subtract,
+	Multiply,
+	Divide,
+	Remainder,
+	And,
+	Or,
+}
+
 impl <'a> Expression<'a> {
 	pub fn add<'b>(left: Box<Expression<'b>>, right: Box<Expression<'b>>) -> Box<Expression<'b>> {
 		Box::new(Expression::ArithmeticExpression(ArithmeticExpression {
@@ -267,10 +435,84 @@ impl <'a> Expression<'a> {
 	pub fn not<'b>(expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
 		Box::new(Expression::Not(expression))
 	}
+	pub fn minus<'b>(expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::Minus(expression))
+	}
+	pub fn plus<'b>(expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::Plus(expression))
+	}
 	pub fn assign<'b>(name: Box<Expression<'b>>, expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
 		Box::new(Expression::Assign {
 			name,
 			expression,
 		})
 	}
+	pub fn add_assign<'b>(name: Box<Expression<'b>>, expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::CompoundAssign {
+			operation: AssignOperation::Add,
+			name,
+			expression,
+		})
+	}
+	pub fn subtract_assign<'b>(name: Box<Expression<'b>>, expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::CompoundAssign {
+			operation: AssignOperation::Subtract,
+			name,
+			expression,
+		})
+	}
+	pub fn multiply_assign<'b>(name: Box<Expression<'b>>, expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::CompoundAssign {
+			operation: AssignOperation::Multiply,
+			name,
+			expression,
+		})
+	}
+	pub fn divide_assign<'b>(name: Box<Expression<'b>>, expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::CompoundAssign {
+			operation: AssignOperation::Divide,
+			name,
+			expression,
+		})
+	}
+	pub fn remainder_assign<'b>(name: Box<Expression<'b>>, expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::CompoundAssign {
+			operation: AssignOperation::Remainder,
+			name,
+			expression,
+		})
+	}
+	pub fn and_assign<'b>(name: Box<Expression<'b>>, expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::CompoundAssign {
+			operation: AssignOperation::And,
+			name,
+			expression,
+		})
+	}
+	pub fn or_assign<'b>(name: Box<Expression<'b>>, expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::CompoundAssign {
+			operation: AssignOperation::Or,
+			name,
+			expression,
+		})
+	}
+	pub fn prefix_increment<'b>(expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::PrefixIncrement(expression))
+	}
+	pub fn prefix_decrement<'b>(expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::PrefixDecrement(expression))
+	}
+	pub fn postfix_increment<'b>(expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::PostfixIncrement(expression))
+	}
+	pub fn postfix_decrement<'b>(expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::PostfixDecrement(expression))
+	}
+	pub fn conditional<'b>(condition: Box<Expression<'b>>, then_expression: Box<Expression<'b>>, else_expression: Box<Expression<'b>>) -> Box<Expression<'b>> {
+		Box::new(Expression::Conditional {
+			condition,
+			then_expression,
+			else_expression,
+		})
+	}
 }
@@ -1,91 +1,199 @@
+use std::collections::{HashMap, HashSet};
 use crate::printer::{Printer, comma_separated};
-use crate::ast::{Program, Function, Class, Statement, Expression};
+use crate::ast::{Program, Function, Class, Statement, Expression, Type};
+use crate::type_checker::Annotation;
 
-pub fn generate<W: std::io::Write>(printer: &mut Printer<W>, program: &Program) {
+// annotations contains the expressions whose translation depends on their types, as determined by the type checker
+pub fn generate<W: std::io::Write>(printer: &mut Printer<W>, program: &Program, annotations: &HashMap<* const Expression, Annotation>) -> std::io::Result<()> {
 	for function in &program.functions {
-		generate_function(printer, function);
+		generate_function(printer, function, annotations)?;
 	}
+	let mut generated = HashSet::new();
 	for class in &program.classes {
-		generate_class(printer, class);
+		generate_class(printer, program, class, &mut generated, annotations)?;
 	}
+	Ok(())
 }
 
-fn generate_function<W: std::io::Write>(printer: &mut Printer<W>, function: &Function) {
+fn generate_function<W: std::io::Write>(printer: &mut Printer<W>, function: &Function, annotations: &HashMap<* const Expression, Annotation>) -> std::io::Result<()> {
 	let arguments = function.arguments.iter().map(|(name, _)| name);
-	printer.println(format_args!("function {}({}) {{", function.name, comma_separated(arguments)));
+	printer.println(format_args!("function {}({}) {{", function.name, comma_separated(arguments)))?;
 	printer.indented(|printer| {
 		for statement in &function.statements {
-			generate_statement(printer, statement);
+			generate_statement(printer, statement, annotations)?;
 		}
-	});
-	printer.println("}");
+		Ok(())
+	})?;
+	printer.println("}")
 }
 
-fn generate_class<W: std::io::Write>(printer: &mut Printer<W>, class: &Class) {
-	printer.println(format_args!("class {} {{", class.name));
+fn generate_class<'a, W: std::io::Write>(printer: &mut Printer<W>, program: &'a Program<'a>, class: &'a Class<'a>, generated: &mut HashSet<&'a str>, annotations: &HashMap<* const Expression, Annotation>) -> std::io::Result<()> {
+	if !generated.insert(class.name) {
+		return Ok(());
+	}
+	// a parent class has to be defined before its subclasses
+	match class.get_parent(program) {
+		Some(parent) => {
+			generate_class(printer, program, parent, generated, annotations)?;
+			printer.println(format_args!("class {} extends {} {{", class.name, parent.name))?;
+		},
+		None => printer.println(format_args!("class {} {{", class.name))?,
+	};
 	printer.indented(|printer| {
-		for (name, ty) in &class.fields {
-			
+		for field in &class.fields {
+			match &field.initializer {
+				Some(initializer) => printer.println_lines(format_args!("{} = {};", field.name, DisplayExpression(initializer, annotations)))?,
+				None => printer.println(format_args!("{} = {};", field.name, default_value(&field.ty)))?,
+			};
 		}
 		for method in &class.methods {
-			generate_method(printer, method);
+			if method.is_abstract {
+				// abstract methods can still be called from JS, so make sure they fail loudly
+				let arguments = method.arguments.iter().map(|(name, _)| name);
+				printer.println(format_args!("{}({}) {{", method.name, comma_separated(arguments)))?;
+				printer.indented(|printer| {
+					printer.println(format_args!("throw new Error(\"abstract method {}.{} is not implemented\");", class.name, method.name))
+				})?;
+				printer.println("}")?;
+			} else {
+				generate_method(printer, method, annotations)?;
+			}
 		}
-	});
-	printer.println("}");
+		Ok(())
+	})?;
+	printer.println("}")
 }
 
-fn generate_method<W: std::io::Write>(printer: &mut Printer<W>, function: &Function) {
+// the value of a field without an initializer
+fn default_value(ty: &Type) -> &'static str {
+	match ty {
+		Type::Number => "0",
+		Type::Boolean => "false",
+		Type::String => "\"\"",
+		_ => "null",
+	}
+}
+
+fn generate_method<W: std::io::Write>(printer: &mut Printer<W>, function: &Function, annotations: &HashMap<* const Expression, Annotation>) -> std::io::Result<()> {
 	let arguments = function.arguments.iter().map(|(name, _)| name);
-	printer.println(format_args!("{}({}) {{", function.name, comma_separated(arguments)));
+	printer.println(format_args!("{}({}) {{", function.name, comma_separated(arguments)))?;
 	printer.indented(|printer| {
 		for statement in &function.statements {
-			generate_statement(printer, statement);
+			generate_statement(printer, statement, annotations)?;
 		}
-	});
-	printer.println("}");
+		Ok(())
+	})?;
+	printer.println("}")
 }
 
-fn generate_statement<W: std::io::Write>(printer: &mut Printer<W>, statement: &Statement) {
+fn generate_statement<W: std::io::Write>(printer: &mut Printer<W>, statement: &Statement, annotations: &HashMap<* const Expression, Annotation>) -> std::io::Result<()> {
 	match statement {
-		Statement::VariableDeclaration { name, expression } => {
-			printer.println(format_args!("let {} = {};", name, DisplayExpression(expression)));
+		Statement::VariableDeclaration { name, expression, constant, .. } => {
+			let keyword = if *constant { "const" } else { "let" };
+			match expression {
+				Some(expression) => printer.println_lines(format_args!("{} {} = {};", keyword, name, DisplayExpression(expression, annotations)))?,
+				None => printer.println(format_args!("{} {};", keyword, name))?,
+			};
 		},
-		Statement::If(crate::ast::If{condition, statement, else_statement}) => {
-			printer.println(format_args!("if ({})", DisplayExpression(condition)));
-			printer.indented(|printer| generate_statement(printer, statement));
+		Statement::If(crate::ast::If{condition, statement, else_statement, ..}) => {
+			printer.println_lines(format_args!("if ({})", DisplayExpression(condition, annotations)))?;
+			printer.indented(|printer| generate_statement(printer, statement, annotations))?;
 			if let Some(statement) = else_statement {
-				printer.println("else");
-				printer.indented(|printer| generate_statement(printer, statement));
+				printer.println("else")?;
+				printer.indented(|printer| generate_statement(printer, statement, annotations))?;
+			}
+		},
+		Statement::While(crate::ast::While{condition, statement, ..}) => {
+			printer.println_lines(format_args!("while ({})", DisplayExpression(condition, annotations)))?;
+			printer.indented(|printer| generate_statement(printer, statement, annotations))?;
+		},
+		Statement::DoWhile(crate::ast::DoWhile{statement, condition, ..}) => {
+			printer.println("do")?;
+			printer.indented(|printer| generate_statement(printer, statement, annotations))?;
+			printer.println_lines(format_args!("while ({});", DisplayExpression(condition, annotations)))?;
+		},
+		Statement::For(crate::ast::For{initializer, condition, step, statement, ..}) => {
+			let initializer = match initializer.as_deref() {
+				Some(Statement::VariableDeclaration { name, expression: Some(expression), constant, .. }) => format!("{} {} = {}", if *constant { "const" } else { "let" }, name, DisplayExpression(expression, annotations)),
+				Some(Statement::VariableDeclaration { name, expression: None, .. }) => format!("let {}", name),
+				Some(Statement::Expression(expression)) => format!("{}", DisplayExpression(expression, annotations)),
+				_ => String::new(),
+			};
+			let condition = condition.as_deref().map(|condition| format!(" {}", DisplayExpression(condition, annotations))).unwrap_or_default();
+			let step = step.as_deref().map(|step| format!(" {}", DisplayExpression(step, annotations))).unwrap_or_default();
+			printer.println_lines(format_args!("for ({};{};{})", initializer, condition, step))?;
+			printer.indented(|printer| generate_statement(printer, statement, annotations))?;
+		},
+		Statement::ForOf(crate::ast::ForOf{name, constant, iterable, statement, ..}) => {
+			let keyword = if *constant { "const" } else { "let" };
+			match iterable {
+				crate::ast::Iterable::Array(array) => {
+					printer.println_lines(format_args!("for ({} {} of {})", keyword, name, DisplayExpression(array, annotations)))?;
+					printer.indented(|printer| generate_statement(printer, statement, annotations))?;
+				},
+				crate::ast::Iterable::Range { start, end } => {
+					// a counting loop instead of an array of the numbers, $ cannot appear in names so the counter does not shadow anything
+					// the loop variable is a copy of the counter so that assigning to it does not affect the iteration
+					printer.println_lines(format_args!("for (let $i = {}, $end = {}; $i < $end; $i++)", DisplayExpression(start, annotations), DisplayExpression(end, annotations)))?;
+					printer.indented(|printer| {
+						printer.println('{')?;
+						printer.indented(|printer| {
+							printer.println(format_args!("{} {} = $i;", keyword, name))?;
+							generate_statement(printer, statement, annotations)
+						})?;
+						printer.println('}')
+					})?;
+				},
 			}
 		},
-		Statement::While(crate::ast::While{condition, statement}) => {
-			printer.println(format_args!("while ({})", DisplayExpression(condition)));
-			printer.indented(|printer| generate_statement(printer, statement));
+		Statement::Break { label, .. } => {
+			match label {
+				Some(label) => printer.println(format_args!("break {};", label))?,
+				None => printer.println("break;")?,
+			};
+		},
+		Statement::Continue { label, .. } => {
+			match label {
+				Some(label) => printer.println(format_args!("continue {};", label))?,
+				None => printer.println("continue;")?,
+			};
 		},
-		Statement::Return(expression) => {
-			printer.println(format_args!("return {};", DisplayExpression(expression)));
+		Statement::Labeled { label, statement, .. } => {
+			printer.println(format_args!("{}:", label))?;
+			generate_statement(printer, statement, annotations)?;
+		},
+		Statement::Return { expression, .. } => {
+			match expression {
+				Some(expression) => printer.println_lines(format_args!("return {};", DisplayExpression(expression, annotations)))?,
+				None => printer.println("return;")?,
+			};
 		},
 		Statement::Expression(expression) => {
-			printer.println(format_args!("{};", DisplayExpression(expression)));
+			printer.println_lines(format_args!("{};", DisplayExpression(expression, annotations)))?;
 		},
 		Statement::Block(statements) => {
-			printer.println('{');
+			printer.println('{')?;
 			printer.indented(|printer| {
 				for statement in statements {
-					generate_statement(printer, statement);
+					generate_statement(printer, statement, annotations)?;
 				}
-			});
-			printer.println('}');
+				Ok(())
+			})?;
+			printer.println('}')?;
 		},
 	}
+	Ok(())
 }
 
-struct DisplayExpression<'a>(&'a Expression<'a>);
+struct DisplayExpression<'a>(&'a Expression<'a>, &'a HashMap<* const Expression<'a>, Annotation>);
 
 impl <'a> std::fmt::Display for DisplayExpression<'a> {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		match self.0 {
-			Expression::Number(s) => write!(f, "{}", s)?,
+			Expression::Number(n) => write!(f, "{}", DisplayNumber(*n))?,
+			Expression::Boolean(b) => write!(f, "{}", b)?,
+			Expression::String(s) => write!(f, "\"{}\"", EscapeString(s))?,
+			Expression::Null => write!(f, "null")?,
 			Expression::Name(s) => write!(f, "{}", s)?,
 			Expression::ArithmeticExpression(e) => {
 				use crate::ast::ArithmeticOperation::*;
@@ -96,7 +204,7 @@ impl <'a> std::fmt::Display for DisplayExpression<'a> {
 					Divide => "/",
 					Remainder => "%",
 				};
-				write!(f, "({} {} {})", DisplayExpression(&e.left), operation, DisplayExpression(&e.right))?;
+				write!(f, "({} {} {})", DisplayExpression(&e.left, self.1), operation, DisplayExpression(&e.right, self.1))?;
 			},
 			Expression::RelationalExpression(e) => {
 				use crate::ast::RelationalOperation::*;
@@ -108,7 +216,7 @@ impl <'a> std::fmt::Display for DisplayExpression<'a> {
 					GreaterThan => ">",
 					GreaterThanOrEqual => ">=",
 				};
-				write!(f, "({} {} {})", DisplayExpression(&e.left), operation, DisplayExpression(&e.right))?;
+				write!(f, "({} {} {})", DisplayExpression(&e.left, self.1), operation, DisplayExpression(&e.right, self.1))?;
 			},
 			Expression::LogicalExpression(e) => {
 				use crate::ast::LogicalOperation::*;
@@ -116,29 +224,125 @@ impl <'a> std::fmt::Display for DisplayExpression<'a> {
 					And => "&&",
 					Or => "||",
 				};
-				write!(f, "({} {} {})", DisplayExpression(&e.left), operation, DisplayExpression(&e.right))?;
+				write!(f, "({} {} {})", DisplayExpression(&e.left, self.1), operation, DisplayExpression(&e.right, self.1))?;
 			},
-			Expression::Not(e) => write!(f, "!{}", DisplayExpression(e))?,
+			Expression::Not(e) => write!(f, "!{}", DisplayExpression(e, self.1))?,
+			// parenthesized so that nested signs are not merged into -- or ++
+			Expression::Minus(e) => write!(f, "(-{})", DisplayExpression(e, self.1))?,
+			Expression::Plus(e) => write!(f, "(+{})", DisplayExpression(e, self.1))?,
 			Expression::Assign { name, expression } => {
-				write!(f, "({} = {})", DisplayExpression(name), DisplayExpression(expression))?;
+				write!(f, "({} = {})", DisplayExpression(name, self.1), DisplayExpression(expression, self.1))?;
+			},
+			Expression::CompoundAssign { operation, name, expression } => {
+				use crate::ast::AssignOperation::*;
+				let operation = match operation {
+					Add => "+=",
+					Subtract => "-=",
+					Multiply => "*=",
+					Divide => "/=",
+					Remainder => "%=",
+					And => "&&=",
+					Or => "||=",
+				};
+				write!(f, "({} {} {})", DisplayExpression(name, self.1), operation, DisplayExpression(expression, self.1))?;
 			},
-			Expression::Call { function, arguments } => {
-				let arguments = arguments.iter().map(|argument| DisplayExpression(argument));
-				write!(f, "{}({})", DisplayExpression(function), comma_separated(arguments))?;
+			Expression::PrefixIncrement(e) => write!(f, "(++{})", DisplayExpression(e, self.1))?,
+			Expression::PrefixDecrement(e) => write!(f, "(--{})", DisplayExpression(e, self.1))?,
+			Expression::PostfixIncrement(e) => write!(f, "({}++)", DisplayExpression(e, self.1))?,
+			Expression::PostfixDecrement(e) => write!(f, "({}--)", DisplayExpression(e, self.1))?,
+			Expression::Conditional { condition, then_expression, else_expression } => {
+				write!(f, "({} ? {} : {})", DisplayExpression(condition, self.1), DisplayExpression(then_expression, self.1), DisplayExpression(else_expression, self.1))?;
 			},
-			Expression::ClassInstantiation { class, arguments } => {
-				let arguments = arguments.iter().map(|argument| DisplayExpression(argument));
+			Expression::Call { function, arguments, .. } => {
+				let arguments = arguments.iter().map(|argument| DisplayExpression(argument, self.1));
+				write!(f, "{}({})", DisplayExpression(function, self.1), comma_separated(arguments))?;
+			},
+			Expression::ClassInstantiation { class, arguments, .. } => {
+				let arguments = arguments.iter().map(|argument| DisplayExpression(argument, self.1));
 				write!(f, "new {}({})", class, comma_separated(arguments))?;
 			},
 			Expression::PropertyAccess { object, property } => {
-				write!(f, "{}.{}", DisplayExpression(object), property)?;
+				if let Some(Annotation::MethodReference) = self.1.get(&(self.0 as * const Expression)) {
+					// a method that is used as a value has to be bound to its object
+					match **object {
+						Expression::Name(_) | Expression::This => write!(f, "{0}.{1}.bind({0})", DisplayExpression(object, self.1), property)?,
+						_ => write!(f, "((object) => object.{}.bind(object))({})", property, DisplayExpression(object, self.1))?,
+					}
+				} else {
+					write!(f, "{}.{}", DisplayExpression(object, self.1), property)?;
+				}
 			},
 			Expression::MethodCall { object, method, arguments } => {
-				let arguments = arguments.iter().map(|argument| DisplayExpression(argument));
-				write!(f, "{}.{}({})", DisplayExpression(object), method, comma_separated(arguments))?;
+				let arguments = arguments.iter().map(|argument| DisplayExpression(argument, self.1));
+				if let Some(Annotation::UndefinedToNull) = self.1.get(&(self.0 as * const Expression)) {
+					write!(f, "({}.{}({}) ?? null)", DisplayExpression(object, self.1), method, comma_separated(arguments))?;
+				} else {
+					write!(f, "{}.{}({})", DisplayExpression(object, self.1), method, comma_separated(arguments))?;
+				}
 			},
 			Expression::This => write!(f, "this")?,
+			Expression::Array(elements) => {
+				let elements = elements.iter().map(|element| DisplayExpression(element, self.1));
+				write!(f, "[{}]", comma_separated(elements))?;
+			},
+			Expression::Index { array, index } => write!(f, "{}[{}]", DisplayExpression(array, self.1), DisplayExpression(index, self.1))?,
+			Expression::Lambda { arguments, body, .. } => {
+				let arguments = arguments.iter().map(|(name, _)| name);
+				match &**body {
+					Statement::Return { expression: Some(expression), .. } => {
+						write!(f, "(({}) => {})", comma_separated(arguments), DisplayExpression(expression, self.1))?;
+					},
+					body => {
+						// the printer indents the lines of the body together with the statement that contains the lambda
+						let mut buffer = Vec::new();
+						generate_statement(&mut Printer::new(&mut buffer), body, self.1).map_err(|_| std::fmt::Error)?;
+						write!(f, "(({}) => {})", comma_separated(arguments), String::from_utf8(buffer).unwrap().trim_end())?;
+					},
+				}
+			},
+			Expression::SuperCall { arguments } => {
+				let arguments = arguments.iter().map(|argument| DisplayExpression(argument, self.1));
+				write!(f, "super({})", comma_separated(arguments))?;
+			},
+			Expression::SuperMethodCall { method, arguments } => {
+				let arguments = arguments.iter().map(|argument| DisplayExpression(argument, self.1));
+				write!(f, "super.{}({})", method, comma_separated(arguments))?;
+			},
 		};
 		Ok(())
 	}
 }
+
+struct DisplayNumber(f64);
+
+impl std::fmt::Display for DisplayNumber {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		// like Number.prototype.toString, print the shortest round-tripping digits and only use exponential notation for very large and very small numbers
+		let n = self.0.abs();
+		if n >= 1e21 || (n != 0.0 && n < 1e-6) {
+			write!(f, "{:e}", self.0)
+		} else {
+			write!(f, "{}", self.0)
+		}
+	}
+}
+
+struct EscapeString<'a>(&'a str);
+
+impl <'a> std::fmt::Display for EscapeString<'a> {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		for c in self.0.chars() {
+			match c {
+				'"' => write!(f, "\\\"")?,
+				'\\' => write!(f, "\\\\")?,
+				'\n' => write!(f, "\\n")?,
+				'\r' => write!(f, "\\r")?,
+				'\t' => write!(f, "\\t")?,
+				// control characters as well as the line and paragraph separators cannot appear unescaped
+				'\u{0}'..='\u{1F}' | '\u{7F}' | '\u{2028}' | '\u{2029}' => write!(f, "\\u{:04x}", c as u32)?,
+				_ => write!(f, "{}", c)?,
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+use crate::ast::{Statement, Expression, If, While, DoWhile, For, ForOf, Iterable};
+use crate::error::Error;
+
+pub enum Node<'a, 'b> {
+	Statement(&'b Statement<'a>),
+	Expression(&'b Expression<'a>),
+}
+
+pub enum Terminator<'a, 'b> {
+	Goto(usize),
+	Branch {
+		// the condition of a for of loop, whether there is another element, is not an expression
+		condition: Option<&'b Expression<'a>>,
+		then_block: usize,
+		else_block: usize,
+	},
+	Return,
+	End,
+}
+
+pub struct BasicBlock<'a, 'b> {
+	pub nodes: Vec<Node<'a, 'b>>,
+	pub terminator: Terminator<'a, 'b>,
+}
+
+impl <'a, 'b> BasicBlock<'a, 'b> {
+	pub fn successors(&self) -> Vec<usize> {
+		match self.terminator {
+			Terminator::Goto(block) => vec![block],
+			Terminator::Branch { then_block, else_block, .. } => vec![then_block, else_block],
+			Terminator::Return | Terminator::End => Vec::new(),
+		}
+	}
+}
+
+struct Loop<'a> {
+	label: Option<&'a str>,
+	continue_block: usize,
+	break_block: usize,
+}
+
+// a control flow graph of a function body; block 0 is the entry block
+pub struct ControlFlowGraph<'a, 'b> {
+	pub blocks: Vec<BasicBlock<'a, 'b>>,
+	entry_blocks: HashMap<* const Statement<'a>, usize>,
+	loops: Vec<Loop<'a>>,
+}
+
+impl <'a, 'b> ControlFlowGraph<'a, 'b> {
+	pub fn new(statements: &'b [Statement<'a>]) -> Self {
+		let mut graph = ControlFlowGraph {
+			blocks: Vec::new(),
+			entry_blocks: HashMap::new(),
+			loops: Vec::new(),
+		};
+		let entry = graph.add_block();
+		graph.add_statements(entry, statements);
+		graph
+	}
+	fn add_block(&mut self) -> usize {
+		self.blocks.push(BasicBlock {
+			nodes: Vec::new(),
+			terminator: Terminator::End,
+		});
+		self.blocks.len() - 1
+	}
+	// adds the statements to the graph starting in the given block and returns the block in which control flow continues
+	fn add_statements(&mut self, mut block: usize, statements: &'b [Statement<'a>]) -> usize {
+		for statement in statements {
+			block = self.add_statement(block, statement);
+		}
+		block
+	}
+	fn add_statement(&mut self, block: usize, statement: &'b Statement<'a>) -> usize {
+		self.entry_blocks.insert(statement, block);
+		match statement {
+			Statement::VariableDeclaration { .. } | Statement::Expression(_) => {
+				self.blocks[block].nodes.push(Node::Statement(statement));
+				block
+			},
+			Statement::Return { .. } => {
+				self.blocks[block].nodes.push(Node::Statement(statement));
+				self.blocks[block].terminator = Terminator::Return;
+				self.add_block()
+			},
+			Statement::Block(statements) => {
+				self.add_statements(block, statements)
+			},
+			Statement::If(If{condition, statement, else_statement, ..}) => {
+				let then_block = self.add_block();
+				let else_block = self.add_block();
+				self.blocks[block].terminator = Terminator::Branch {
+					condition: Some(condition),
+					then_block,
+					else_block,
+				};
+				let then_block = self.add_statement(then_block, statement);
+				let else_block = match else_statement {
+					Some(else_statement) => self.add_statement(else_block, else_statement),
+					None => else_block,
+				};
+				let next_block = self.add_block();
+				self.blocks[then_block].terminator = Terminator::Goto(next_block);
+				self.blocks[else_block].terminator = Terminator::Goto(next_block);
+				next_block
+			},
+			Statement::While(_) | Statement::DoWhile(_) | Statement::For(_) | Statement::ForOf(_) => {
+				self.add_loop(block, statement, None)
+			},
+			Statement::Break { label, .. } => {
+				let target = self.get_loop(*label).map(|l| l.break_block);
+				self.add_jump(block, target)
+			},
+			Statement::Continue { label, .. } => {
+				let target = self.get_loop(*label).map(|l| l.continue_block);
+				self.add_jump(block, target)
+			},
+			Statement::Labeled { label, statement, .. } => {
+				self.entry_blocks.insert(&**statement, block);
+				self.add_loop(block, statement, Some(label))
+			},
+		}
+	}
+	fn add_loop(&mut self, block: usize, statement: &'b Statement<'a>, label: Option<&'a str>) -> usize {
+		match statement {
+			Statement::While(While{condition, statement, ..}) => {
+				let condition_block = self.add_block();
+				let body_block = self.add_block();
+				let next_block = self.add_block();
+				self.blocks[block].terminator = Terminator::Goto(condition_block);
+				self.blocks[condition_block].terminator = loop_condition(Some(condition), body_block, next_block);
+				let body_block = self.add_loop_body(body_block, statement, label, condition_block, next_block);
+				self.blocks[body_block].terminator = Terminator::Goto(condition_block);
+				next_block
+			},
+			Statement::DoWhile(DoWhile{statement, condition, ..}) => {
+				let body_block = self.add_block();
+				let condition_block = self.add_block();
+				let next_block = self.add_block();
+				self.blocks[block].terminator = Terminator::Goto(body_block);
+				self.blocks[condition_block].terminator = loop_condition(Some(condition), body_block, next_block);
+				let body_block = self.add_loop_body(body_block, statement, label, condition_block, next_block);
+				self.blocks[body_block].terminator = Terminator::Goto(condition_block);
+				next_block
+			},
+			Statement::For(For{initializer, condition, step, statement, ..}) => {
+				let block = match initializer {
+					Some(initializer) => self.add_statement(block, initializer),
+					None => block,
+				};
+				let condition_block = self.add_block();
+				let body_block = self.add_block();
+				let step_block = self.add_block();
+				let next_block = self.add_block();
+				self.blocks[block].terminator = Terminator::Goto(condition_block);
+				self.blocks[condition_block].terminator = loop_condition(condition.as_deref(), body_block, next_block);
+				let body_block = self.add_loop_body(body_block, statement, label, step_block, next_block);
+				self.blocks[body_block].terminator = Terminator::Goto(step_block);
+				if let Some(step) = step {
+					self.blocks[step_block].nodes.push(Node::Expression(step));
+				}
+				self.blocks[step_block].terminator = Terminator::Goto(condition_block);
+				next_block
+			},
+			Statement::ForOf(ForOf{iterable, statement, ..}) => {
+				// the iterable is evaluated once before the loop
+				match iterable {
+					Iterable::Array(array) => self.blocks[block].nodes.push(Node::Expression(array)),
+					Iterable::Range { start, end } => {
+						self.blocks[block].nodes.push(Node::Expression(start));
+						self.blocks[block].nodes.push(Node::Expression(end));
+					},
+				}
+				let condition_block = self.add_block();
+				let body_block = self.add_block();
+				let next_block = self.add_block();
+				self.blocks[block].terminator = Terminator::Goto(condition_block);
+				self.blocks[condition_block].terminator = Terminator::Branch {
+					condition: None,
+					then_block: body_block,
+					else_block: next_block,
+				};
+				let body_block = self.add_loop_body(body_block, statement, label, condition_block, next_block);
+				self.blocks[body_block].terminator = Terminator::Goto(condition_block);
+				next_block
+			},
+			// labels on other statements are rejected by the type checker
+			_ => self.add_statement(block, statement),
+		}
+	}
+	fn add_loop_body(&mut self, block: usize, statement: &'b Statement<'a>, label: Option<&'a str>, continue_block: usize, break_block: usize) -> usize {
+		self.loops.push(Loop {
+			label,
+			continue_block,
+			break_block,
+		});
+		let block = self.add_statement(block, statement);
+		self.loops.pop();
+		block
+	}
+	fn get_loop(&self, label: Option<&str>) -> Option<&Loop<'a>> {
+		match label {
+			Some(label) => self.loops.iter().rev().find(|l| l.label == Some(label)),
+			None => self.loops.last(),
+		}
+	}
+	fn add_jump(&mut self, block: usize, target: Option<usize>) -> usize {
+		// jumps without a target are rejected by the type checker
+		self.blocks[block].terminator = match target {
+			Some(target) => Terminator::Goto(target),
+			None => Terminator::Return,
+		};
+		self.add_block()
+	}
+	// computes the state at the beginning of every block by propagating the state at the entry along the edges until a fixed point is reached, unreachable blocks have no state
+	pub fn analyze<A: Analysis<'a>>(&self, analysis: &A, entry: A::State) -> Result<Vec<Option<A::State>>, Error> {
+		let mut states = vec![None; self.blocks.len()];
+		states[0] = Some(entry);
+		let mut worklist = vec![0];
+		while let Some(block) = worklist.pop() {
+			let mut state = states[block].clone().unwrap();
+			analysis.transfer(&self.blocks[block], &mut state)?;
+			for successor in self.blocks[block].successors() {
+				let successor_state = match &states[successor] {
+					Some(successor_state) => analysis.join(successor_state, &state),
+					None => state.clone(),
+				};
+				if states[successor].as_ref() != Some(&successor_state) {
+					states[successor] = Some(successor_state);
+					worklist.push(successor);
+				}
+			}
+		}
+		Ok(states)
+	}
+	pub fn reachable_blocks(&self) -> Vec<bool> {
+		let mut reachable = vec![false; self.blocks.len()];
+		let mut stack = vec![0];
+		while let Some(block) = stack.pop() {
+			if !reachable[block] {
+				reachable[block] = true;
+				stack.extend(self.blocks[block].successors());
+			}
+		}
+		reachable
+	}
+	// whether control flow can reach the end of the function without a return statement
+	pub fn falls_through(&self, reachable: &[bool]) -> bool {
+		self.blocks.iter().zip(reachable).any(|(block, reachable)| *reachable && matches!(block.terminator, Terminator::End))
+	}
+	pub fn is_reachable(&self, reachable: &[bool], statement: &Statement<'a>) -> bool {
+		let key: * const Statement = statement;
+		match self.entry_blocks.get(&key) {
+			Some(block) => reachable[*block],
+			None => false,
+		}
+	}
+}
+
+fn loop_condition<'a, 'b>(condition: Option<&'b Expression<'a>>, body_block: usize, next_block: usize) -> Terminator<'a, 'b> {
+	match condition {
+		// an infinite loop can only be left through a break or a return
+		None | Some(Expression::Boolean(true)) => Terminator::Goto(body_block),
+		Some(condition) => Terminator::Branch {
+			condition: Some(condition),
+			then_block: body_block,
+			else_block: next_block,
+		},
+	}
+}
+
+// a forward analysis whose state flows through the nodes of a block in evaluation order
+// the join has to be monotone so that any error found on the way to the fixed point is also an error at the fixed point
+pub trait Analysis<'a> {
+	type State: Clone + PartialEq;
+	fn join(&self, a: &Self::State, b: &Self::State) -> Self::State;
+	// called before the subexpressions are visited, returns false if it already took care of the subexpressions
+	fn visit_expression(&self, expression: &Expression<'a>, state: &mut Self::State) -> Result<bool, Error>;
+	// called after the expressions of the statement have been visited
+	fn visit_statement(&self, _statement: &Statement<'a>, _state: &mut Self::State) -> Result<(), Error> {
+		Ok(())
+	}
+	// called when control flow leaves the function, either through a return statement or by reaching the end
+	fn visit_exit(&self, _block: &BasicBlock<'a, '_>, _state: &Self::State) -> Result<(), Error> {
+		Ok(())
+	}
+	fn transfer(&self, block: &BasicBlock<'a, '_>, state: &mut Self::State) -> Result<(), Error> {
+		for node in &block.nodes {
+			match node {
+				Node::Statement(statement) => {
+					match statement {
+						Statement::VariableDeclaration { expression: Some(expression), .. } | Statement::Expression(expression) | Statement::Return { expression: Some(expression), .. } => {
+							self.walk_expression(expression, state)?;
+						},
+						_ => {},
+					}
+					self.visit_statement(statement, state)?;
+				},
+				Node::Expression(expression) => self.walk_expression(expression, state)?,
+			}
+		}
+		match block.terminator {
+			Terminator::Branch { condition: Some(condition), .. } => self.walk_expression(condition, state),
+			Terminator::Branch { condition: None, .. } => Ok(()),
+			Terminator::Return | Terminator::End => self.visit_exit(block, state),
+			Terminator::Goto(_) => Ok(()),
+		}
+	}
+	fn walk_expression(&self, expression: &Expression<'a>, state: &mut Self::State) -> Result<(), Error> {
+		use crate::ast::Expression::*;
+		if !self.visit_expression(expression, state)? {
+			return Ok(());
+		}
+		match expression {
+			// the body of a lambda does not run when the lambda is created
+			Number(_) | Boolean(_) | String(_) | Null | Name(_) | This | Lambda { .. } => {},
+			ArithmeticExpression(e) => {
+				self.walk_expression(&e.left, state)?;
+				self.walk_expression(&e.right, state)?;
+			},
+			RelationalExpression(e) => {
+				self.walk_expression(&e.left, state)?;
+				self.walk_expression(&e.right, state)?;
+			},
+			LogicalExpression(e) => {
+				self.walk_expression(&e.left, state)?;
+				let mut right_state = state.clone();
+				self.walk_expression(&e.right, &mut right_state)?;
+				*state = self.join(state, &right_state);
+			},
+			Not(e) | Minus(e) | Plus(e) | PrefixIncrement(e) | PrefixDecrement(e) | PostfixIncrement(e) | PostfixDecrement(e) => {
+				self.walk_expression(e, state)?;
+			},
+			Assign { name, expression } => {
+				self.walk_expression(name, state)?;
+				self.walk_expression(expression, state)?;
+			},
+			CompoundAssign { operation, name, expression } => {
+				self.walk_expression(name, state)?;
+				match operation {
+					crate::ast::AssignOperation::And | crate::ast::AssignOperation::Or => {
+						let mut right_state = state.clone();
+						self.walk_expression(expression, &mut right_state)?;
+						*state = self.join(state, &right_state);
+					},
+					_ => self.walk_expression(expression, state)?,
+				}
+			},
+			Conditional { condition, then_expression, else_expression } => {
+				self.walk_expression(condition, state)?;
+				let mut else_state = state.clone();
+				self.walk_expression(then_expression, state)?;
+				self.walk_expression(else_expression, &mut else_state)?;
+				*state = self.join(state, &else_state);
+			},
+			Call { function, arguments, .. } => {
+				self.walk_expression(function, state)?;
+				for argument in arguments {
+					self.walk_expression(argument, state)?;
+				}
+			},
+			ClassInstantiation { arguments, .. } | Array(arguments) => {
+				for argument in arguments {
+					self.walk_expression(argument, state)?;
+				}
+			},
+			Index { array, index } => {
+				self.walk_expression(array, state)?;
+				self.walk_expression(index, state)?;
+			},
+			PropertyAccess { object, .. } => {
+				self.walk_expression(object, state)?;
+			},
+			MethodCall { object, arguments, .. } => {
+				self.walk_expression(object, state)?;
+				for argument in arguments {
+					self.walk_expression(argument, state)?;
+				}
+			},
+			SuperCall { arguments } | SuperMethodCall { arguments, .. } => {
+				for argument in arguments {
+					self.walk_expression(argument, state)?;
+				}
+			},
+		}
+		Ok(())
+	}
+}
@@ -1,4 +1,4 @@
-use crate::printer::{bold, red};
+use crate::printer::{bold, red, yellow};
 
 pub type Location = usize;
 
@@ -10,28 +10,44 @@ pub struct Error {
 impl Error {
 	pub fn print<W: std::io::Write>(&self, s: &str, mut write: W) -> std::io::Result<()> {
 		writeln!(write, "{}: {}", bold(red("error")), self.msg)?;
-		let mut start = 0;
-		let mut end = s.len();
-		let mut num = 0;
-		for (i, c) in s.char_indices() {
-			if c == '\n' {
-				if i < self.i {
-					start = i + c.len_utf8();
-					num += 1;
-				} else {
-					end = i;
-					break;
-				}
+		print_location(self.i, s, write)
+	}
+}
+
+pub struct Warning {
+	pub i: Location,
+	pub msg: String,
+}
+
+impl Warning {
+	pub fn print<W: std::io::Write>(&self, s: &str, mut write: W) -> std::io::Result<()> {
+		writeln!(write, "{}: {}", bold(yellow("warning")), self.msg)?;
+		print_location(self.i, s, write)
+	}
+}
+
+fn print_location<W: std::io::Write>(location: Location, s: &str, mut write: W) -> std::io::Result<()> {
+	let mut start = 0;
+	let mut end = s.len();
+	let mut num = 0;
+	for (i, c) in s.char_indices() {
+		if c == '\n' {
+			if i < location {
+				start = i + c.len_utf8();
+				num += 1;
+			} else {
+				end = i;
+				break;
 			}
 		}
-		let line = s.get(start..end).unwrap();
-		writeln!(write, "{} | {}", num, line)?;
-		write!(write, "{} | ", num)?;
-		for (_, c) in line.char_indices().take_while(|(i, _)| start + *i < self.i) {
-			let c = if c.is_whitespace() { c } else { ' ' };
-			write!(write, "{}", c)?;
-		}
-		writeln!(write, "^")?;
-		Ok(())
 	}
+	let line = s.get(start..end).unwrap();
+	writeln!(write, "{} | {}", num, line)?;
+	write!(write, "{} | ", num)?;
+	for (_, c) in line.char_indices().take_while(|(i, _)| start + *i < location) {
+		let c = if c.is_whitespace() { c } else { ' ' };
+		write!(write, "{}", c)?;
+	}
+	writeln!(write, "^")?;
+	Ok(())
 }
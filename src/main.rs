@@ -5,6 +5,7 @@ mod ast;
 mod type_checker;
 mod scoped_hash_map;
 mod codegen;
+mod control_flow;
 
 use error::{Error, Location};
 use parser::{Parse, optional, repeat, not, peek, sequence, choice, ParseResult};
@@ -12,22 +13,35 @@ use printer::{bold, red, green};
 use ast::Expression;
 
 enum OperatorLevel {
+	TernaryRightToLeft(&'static [TernaryOperator]),
 	BinaryLeftToRight(&'static [BinaryOperator]),
 	BinaryRightToLeft(&'static [BinaryOperator]),
 	UnaryPrefix(&'static [UnaryOperator]),
 	UnaryPostfix(&'static [UnaryOperator]),
 }
 
+type TernaryOperatorFunction = for <'a> fn(Box<Expression<'a>>, Box<Expression<'a>>, Box<Expression<'a>>) -> Box<Expression<'a>>;
 type BinaryOperatorFunction = for <'a> fn(Box<Expression<'a>>, Box<Expression<'a>>) -> Box<Expression<'a>>;
 type UnaryOperatorFunction = for <'a> fn(Box<Expression<'a>>) -> Box<Expression<'a>>;
+struct TernaryOperator(&'static str, &'static str, TernaryOperatorFunction);
 struct BinaryOperator(&'static str, BinaryOperatorFunction);
 struct UnaryOperator(&'static str, UnaryOperatorFunction);
 
-use OperatorLevel::{BinaryLeftToRight, BinaryRightToLeft, UnaryPrefix, UnaryPostfix};
+use OperatorLevel::{TernaryRightToLeft, BinaryLeftToRight, BinaryRightToLeft, UnaryPrefix, UnaryPostfix};
 
 const OPERATORS: &'static [OperatorLevel] = &[
 	BinaryRightToLeft(&[
 		BinaryOperator("=", Expression::assign),
+		BinaryOperator("+=", Expression::add_assign),
+		BinaryOperator("-=", Expression::subtract_assign),
+		BinaryOperator("*=", Expression::multiply_assign),
+		BinaryOperator("/=", Expression::divide_assign),
+		BinaryOperator("%=", Expression::remainder_assign),
+		BinaryOperator("&&=", Expression::and_assign),
+		BinaryOperator("||=", Expression::or_assign),
+	]),
+	TernaryRightToLeft(&[
+		TernaryOperator("?", ":", Expression::conditional),
 	]),
 	BinaryLeftToRight(&[
 		BinaryOperator("||", Expression::or),
@@ -42,8 +56,8 @@ const OPERATORS: &'static [OperatorLevel] = &[
 	BinaryLeftToRight(&[
 		BinaryOperator("<=", Expression::less_than_or_equal),
 		BinaryOperator("<", Expression::less_than),
-		BinaryOperator(">=", Expression::greater_than),
-		BinaryOperator(">", Expression::greater_than_or_equal),
+		BinaryOperator(">=", Expression::greater_than_or_equal),
+		BinaryOperator(">", Expression::greater_than),
 	]),
 	BinaryLeftToRight(&[
 		BinaryOperator("+", Expression::add),
@@ -55,13 +69,25 @@ const OPERATORS: &'static [OperatorLevel] = &[
 		BinaryOperator("%", Expression::remainder),
 	]),
 	UnaryPrefix(&[
+		UnaryOperator("++", Expression::prefix_increment),
+		UnaryOperator("--", Expression::prefix_decrement),
 		UnaryOperator("!", Expression::not),
+		UnaryOperator("-", Expression::minus),
+		UnaryOperator("+", Expression::plus),
+	]),
+	UnaryPostfix(&[
+		UnaryOperator("++", Expression::postfix_increment),
+		UnaryOperator("--", Expression::postfix_decrement),
 	]),
 ];
 
 struct Cursor<'a> {
 	cursor: parser::Cursor<'a>,
 	program: ast::Program<'a>,
+	// the type parameters of the function or class that is currently being parsed
+	type_parameters: Vec<&'a str>,
+	// the parameters and local variables that are visible at the current position
+	locals: Vec<&'a str>,
 }
 
 impl <'a> Cursor<'a> {
@@ -69,6 +95,8 @@ impl <'a> Cursor<'a> {
 		Cursor {
 			cursor: parser::Cursor::new(s),
 			program: ast::Program::new(),
+			type_parameters: Vec::new(),
+			locals: Vec::new(),
 		}
 	}
 	fn error<T, S: Into<String>>(&self, msg: S) -> Result<T, Error> {
@@ -100,13 +128,22 @@ impl <'a> Cursor<'a> {
 		Ok(())
 	}
 	fn parse_expression(&mut self, level: usize) -> Result<Box<Expression<'a>>, Error> {
-		fn parse_binary_operator<'a>(cursor: &mut Cursor<'a>, operators: &'static [BinaryOperator]) -> Option<(BinaryOperatorFunction, Location)> {
+		fn parse_ternary_operator<'a>(cursor: &mut Cursor<'a>, operators: &'static [TernaryOperator]) -> Option<(&'static TernaryOperator, Location)> {
 			for operator in operators {
 				if let Ok((_, location)) = cursor.parse(operator.0) {
+					return Some((operator, location));
+				}
+			}
+			None
+		}
+		fn parse_binary_operator<'a>(cursor: &mut Cursor<'a>, operators: &'static [BinaryOperator]) -> Option<(BinaryOperatorFunction, Location)> {
+			for operator in operators {
+				// make sure not to parse the beginning of a compound assignment
+				if let Ok((_, location)) = cursor.parse(sequence!(operator.0, not('='))) {
 					return Some((operator.1, location));
 				}
 			}
-			return None;
+			None
 		}
 		fn parse_unary_operator<'a>(cursor: &mut Cursor<'a>, operators: &'static [UnaryOperator]) -> Option<(UnaryOperatorFunction, Location)> {
 			for operator in operators {
@@ -114,10 +151,25 @@ impl <'a> Cursor<'a> {
 					return Some((operator.1, location));
 				}
 			}
-			return None;
+			None
 		}
 		if level < OPERATORS.len() {
 			match OPERATORS[level] {
+				TernaryRightToLeft(operators) => {
+					let condition = self.parse_expression(level + 1)?;
+					self.skip_comments()?;
+					if let Some((operator, location)) = parse_ternary_operator(self, operators) {
+						self.skip_comments()?;
+						let then_expression = self.parse_expression(0)?;
+						self.skip_comments()?;
+						self.expect(operator.1)?;
+						self.skip_comments()?;
+						let else_expression = self.parse_expression(level)?;
+						Ok(self.mark_location((operator.2)(condition, then_expression, else_expression), location))
+					} else {
+						Ok(condition)
+					}
+				},
 				BinaryLeftToRight(operators) => {
 					let mut left = self.parse_expression(level + 1)?;
 					self.skip_comments()?;
@@ -160,89 +212,121 @@ impl <'a> Cursor<'a> {
 				},
 			}
 		} else {
-			let mut expression = if let Ok(_) = self.parse('(') {
+			let mut expression = if self.parse(peek(sequence!('(', repeat(char::is_whitespace), choice!(')', sequence!(identifier_start_char, repeat(identifier_char), repeat(char::is_whitespace), ':'))))).is_ok() {
+				self.parse_lambda()?
+			} else if self.parse('(').is_ok() {
 				self.skip_comments()?;
 				let expression = self.parse_expression(0)?;
 				self.skip_comments()?;
 				self.expect(")")?;
 				expression
+			} else if let Ok((_, location)) = self.parse('[') {
+				let elements = self.parse_array_elements()?;
+				self.mark_location(Box::new(Expression::Array(elements)), location)
 			} else if let Ok((_, location)) = self.parse(keyword("new")) {
 				self.skip_comments()?;
 				let (class, _) = self.parse_identifier()?;
 				self.skip_comments()?;
-				self.expect("(")?;
-				let mut arguments = Vec::new();
-				self.skip_comments()?;
-				while let Ok(_) = self.parse(not(')')) {
-					arguments.push(self.parse_expression(0)?);
+				let type_arguments = if self.parse('<').is_ok() {
 					self.skip_comments()?;
-					match self.parse(',') {
-						Ok(_) => {
-							self.skip_comments()?;
-							continue
-						}
-						Err(_) => break
-					}
-				}
-				self.expect(")")?;
+					let type_arguments = self.parse_type_arguments()?;
+					self.skip_comments()?;
+					type_arguments
+				} else {
+					Vec::new()
+				};
+				self.expect("(")?;
+				let arguments = self.parse_call_arguments()?;
 				self.skip_comments()?;
 				self.mark_location(Box::new(Expression::ClassInstantiation {
 					class,
+					type_arguments,
 					arguments,
 				}), location)
+			} else if let Ok((_, location)) = self.parse(keyword("super")) {
+				self.skip_comments()?;
+				if self.parse('(').is_ok() {
+					let arguments = self.parse_call_arguments()?;
+					self.mark_location(Box::new(Expression::SuperCall {
+						arguments,
+					}), location)
+				} else {
+					self.expect(".")?;
+					self.skip_comments()?;
+					let (method, location) = self.parse_identifier()?;
+					self.skip_comments()?;
+					self.expect("(")?;
+					let arguments = self.parse_call_arguments()?;
+					self.mark_location(Box::new(Expression::SuperMethodCall {
+						method,
+						arguments,
+					}), location)
+				}
 			} else if let Ok((_, location)) = self.parse(keyword("this")) {
 				self.mark_location(Box::new(Expression::This), location)
+			} else if let Ok((_, location)) = self.parse(keyword("true")) {
+				self.mark_location(Box::new(Expression::Boolean(true)), location)
+			} else if let Ok((_, location)) = self.parse(keyword("false")) {
+				self.mark_location(Box::new(Expression::Boolean(false)), location)
+			} else if let Ok((_, location)) = self.parse(keyword("null")) {
+				self.mark_location(Box::new(Expression::Null), location)
 			} else if let Ok(_) = self.parse(peek(identifier_start_char)) {
 				let (s, location) = self.parse_identifier()?;
-				self.mark_location(Box::new(Expression::Name(s)), location)
+				let name = self.mark_location(Box::new(Expression::Name(s)), location);
+				// a < after a name starts explicit type arguments only if it is followed by a call, otherwise it is a comparison
+				// local variables shadow generic functions so after them it is always a comparison
+				let saved = self.cursor;
+				self.skip_comments()?;
+				match if self.locals.contains(&s) { None } else { self.parse_explicit_type_arguments() } {
+					Some((type_arguments, location)) => {
+						let arguments = self.parse_call_arguments()?;
+						self.mark_location(Box::new(Expression::Call {
+							function: name,
+							type_arguments,
+							arguments,
+						}), location)
+					},
+					None => {
+						self.cursor = saved;
+						name
+					},
+				}
 			} else if let Ok(_) = self.parse(peek('0'..='9')) {
-				let (s, location) = self.parse_number()?;
-				self.mark_location(Box::new(Expression::Number(s)), location)
+				let (n, location) = self.parse_number()?;
+				self.mark_location(Box::new(Expression::Number(n)), location)
+			} else if self.parse(peek('"')).is_ok() {
+				let (s, location) = self.parse_string()?;
+				self.mark_location(Box::new(Expression::String(s)), location)
 			} else {
 				return self.error("expected an expression");
 			};
 			self.skip_comments()?;
 			loop {
-				if let Ok(_) = self.parse('(') {
-					let mut arguments = Vec::new();
-					self.skip_comments()?;
-					while let Ok(_) = self.parse(not(')')) {
-						arguments.push(self.parse_expression(0)?);
-						self.skip_comments()?;
-						match self.parse(',') {
-							Ok(_) => {
-								self.skip_comments()?;
-								continue
-							}
-							Err(_) => break
-						}
-					}
-					self.parse(')')?;
-					expression = Box::new(Expression::Call {
+				if let Ok((_, location)) = self.parse('(') {
+					let arguments = self.parse_call_arguments()?;
+					expression = self.mark_location(Box::new(Expression::Call {
 						function: expression,
+						type_arguments: Vec::new(),
 						arguments,
-					});
+					}), location);
+					self.skip_comments()?;
+				} else if let Ok((_, location)) = self.parse('[') {
+					self.skip_comments()?;
+					let index = self.parse_expression(0)?;
+					self.skip_comments()?;
+					self.expect("]")?;
+					expression = self.mark_location(Box::new(Expression::Index {
+						array: expression,
+						index,
+					}), location);
 					self.skip_comments()?;
-				} else if let Ok(_) = self.parse('.') {
+				} else if self.parse(sequence!('.', not('.'))).is_ok() {
 					self.skip_comments()?;
 					let (name, location) = self.parse_identifier()?;
 					self.skip_comments()?;
 					if let Ok(_) = self.parse('(') {
 						// method call
-						let mut arguments = Vec::new();
-						self.skip_comments()?;
-						while let Ok(_) = self.parse(not(')')) {
-							arguments.push(self.parse_expression(0)?);
-							self.skip_comments()?;
-							match self.parse(',') {
-								Ok(_) => {
-									self.skip_comments()?;
-									continue
-								}
-								Err(_) => break
-							}
-						}
-						self.parse(')')?;
+						let arguments = self.parse_call_arguments()?;
 						expression = self.mark_location(Box::new(Expression::MethodCall {
 							object: expression,
 							method: name,
@@ -263,39 +347,372 @@ impl <'a> Cursor<'a> {
 			Ok(expression)
 		}
 	}
+	// parses a lambda like (a: number, b: number): boolean => a < b or one whose body is a block
+	fn parse_lambda(&mut self) -> Result<Box<Expression<'a>>, Error> {
+		let (_, location) = self.parse('(')?;
+		self.skip_comments()?;
+		let arguments = self.parse_arguments()?;
+		let locals = self.locals.len();
+		self.locals.extend(arguments.iter().map(|(name, _)| *name));
+		self.skip_comments()?;
+		let return_type = if self.parse(':').is_ok() {
+			self.skip_comments()?;
+			// like in function types void is only allowed as a return type
+			let ty = if self.parse(keyword("void")).is_ok() {
+				ast::Type::Void
+			} else {
+				self.parse_type()?.0
+			};
+			self.skip_comments()?;
+			Some(ty)
+		} else {
+			None
+		};
+		self.expect("=>")?;
+		self.skip_comments()?;
+		let body = if self.parse(peek('{')).is_ok() {
+			self.parse_statement()?
+		} else {
+			ast::Statement::Return {
+				expression: Some(self.parse_expression(0)?),
+				location,
+			}
+		};
+		self.locals.truncate(locals);
+		Ok(self.mark_location(Box::new(Expression::Lambda {
+			arguments,
+			return_type,
+			body: Box::new(body),
+		}), location))
+	}
+	// parses type arguments followed by the opening parenthesis of a call, returns the location of the parenthesis
+	fn parse_explicit_type_arguments(&mut self) -> Option<(Vec<ast::Type<'a>>, Location)> {
+		self.parse('<').ok()?;
+		self.skip_comments().ok()?;
+		let type_arguments = self.parse_type_arguments().ok()?;
+		self.skip_comments().ok()?;
+		let (_, location) = self.parse('(').ok()?;
+		Some((type_arguments, location))
+	}
+	// parses a comma separated list of types after the opening angle bracket
+	fn parse_type_arguments(&mut self) -> Result<Vec<ast::Type<'a>>, Error> {
+		let mut type_arguments = Vec::new();
+		loop {
+			let (ty, _) = self.parse_type()?;
+			type_arguments.push(ty);
+			self.skip_comments()?;
+			match self.parse(',') {
+				Ok(_) => self.skip_comments()?,
+				Err(_) => break,
+			}
+		}
+		self.expect(">")?;
+		Ok(type_arguments)
+	}
+	// parses the arguments of a call after the opening parenthesis
+	fn parse_call_arguments(&mut self) -> Result<Vec<Box<Expression<'a>>>, Error> {
+		let mut arguments = Vec::new();
+		self.skip_comments()?;
+		while self.parse(not(')')).is_ok() {
+			arguments.push(self.parse_expression(0)?);
+			self.skip_comments()?;
+			match self.parse(',') {
+				Ok(_) => {
+					self.skip_comments()?;
+					continue
+				}
+				Err(_) => break
+			}
+		}
+		self.expect(")")?;
+		Ok(arguments)
+	}
+	// parses the elements of an array literal after the opening bracket
+	fn parse_array_elements(&mut self) -> Result<Vec<Box<Expression<'a>>>, Error> {
+		let mut elements = Vec::new();
+		self.skip_comments()?;
+		while self.parse(not(']')).is_ok() {
+			elements.push(self.parse_expression(0)?);
+			self.skip_comments()?;
+			match self.parse(',') {
+				Ok(_) => {
+					self.skip_comments()?;
+					continue
+				}
+				Err(_) => break
+			}
+		}
+		self.expect("]")?;
+		Ok(elements)
+	}
 	fn parse_identifier(&mut self) -> Result<(&'a str, Location), Error> {
 		self.parse(sequence!(identifier_start_char, repeat(identifier_char)))
 	}
-	fn parse_number(&mut self) -> Result<(&'a str, Location), Error> {
-		self.parse(repeat('0'..='9'))
+	fn parse_number(&mut self) -> Result<(f64, Location), Error> {
+		let (_, location) = self.parse("")?;
+		let radix = if self.parse(choice!("0x", "0X")).is_ok() {
+			Some(16)
+		} else if self.parse(choice!("0o", "0O")).is_ok() {
+			Some(8)
+		} else if self.parse(choice!("0b", "0B")).is_ok() {
+			Some(2)
+		} else {
+			None
+		};
+		let n = if let Some(radix) = radix {
+			let digits = self.parse_digits(radix)?;
+			if digits.is_empty() {
+				return self.error("expected a digit");
+			}
+			let mut n: u128 = 0;
+			for digit in digits.chars() {
+				n = match n.checked_mul(radix as u128).and_then(|n| n.checked_add(digit.to_digit(radix).unwrap() as u128)) {
+					Some(n) => n,
+					None => return Err(Error {
+						i: location,
+						msg: "number literal out of range".to_string(),
+					}),
+				};
+			}
+			n as f64
+		} else {
+			let mut s = self.parse_digits(10)?;
+			if s.len() > 1 && s.starts_with('0') {
+				return Err(Error {
+					i: location,
+					msg: "number literals must not have leading zeros".to_string(),
+				});
+			}
+			if self.parse(sequence!('.', peek('0'..='9'))).is_ok() {
+				s.push('.');
+				s.push_str(&self.parse_digits(10)?);
+			}
+			let is_zero = !s.chars().any(|c| ('1'..='9').contains(&c));
+			if let Ok((_, exponent_location)) = self.parse(choice!('e', 'E')) {
+				s.push('e');
+				if let Ok((sign, _)) = self.parse(choice!('+', '-')) {
+					s.push_str(sign);
+				}
+				let exponent = self.parse_digits(10)?;
+				if exponent.is_empty() {
+					return Err(Error {
+						i: exponent_location,
+						msg: "malformed exponent in number literal".to_string(),
+					});
+				}
+				s.push_str(&exponent);
+			}
+			let n = s.parse::<f64>().unwrap();
+			// a literal that is too small would silently become 0, so it is rejected just like one that is too large
+			if n == 0.0 && !is_zero {
+				return Err(Error {
+					i: location,
+					msg: "number literal out of range".to_string(),
+				});
+			}
+			n
+		};
+		if self.parse(peek(identifier_char)).is_ok() {
+			return self.error("invalid character in number literal");
+		}
+		if !n.is_finite() {
+			return Err(Error {
+				i: location,
+				msg: "number literal out of range".to_string(),
+			});
+		}
+		Ok((n, location))
+	}
+	// parses digits of the given radix that may be separated by underscores and returns them without the separators
+	fn parse_digits(&mut self, radix: u32) -> Result<String, Error> {
+		let mut digits = String::new();
+		loop {
+			let (s, _) = self.parse(repeat(|c: char| c.is_digit(radix)))?;
+			digits.push_str(s);
+			if let Ok((_, location)) = self.parse('_') {
+				if s.is_empty() || self.parse(peek(|c: char| c.is_digit(radix))).is_err() {
+					return Err(Error {
+						i: location,
+						msg: "numeric separators are only allowed between digits".to_string(),
+					});
+				}
+			} else {
+				break;
+			}
+		}
+		Ok(digits)
+	}
+	fn parse_string(&mut self) -> Result<(String, Location), Error> {
+		let (_, location) = self.parse('"')?;
+		let mut s = String::new();
+		loop {
+			let (chars, _) = self.parse(repeat(|c| c != '"' && c != '\\' && c != '\n'))?;
+			s.push_str(chars);
+			if self.parse('"').is_ok() {
+				break;
+			} else if let Ok((_, escape_location)) = self.parse('\\') {
+				let c = if self.parse('n').is_ok() {
+					'\n'
+				} else if self.parse('r').is_ok() {
+					'\r'
+				} else if self.parse('t').is_ok() {
+					'\t'
+				} else if self.parse('0').is_ok() {
+					'\0'
+				} else if self.parse('\\').is_ok() {
+					'\\'
+				} else if self.parse('"').is_ok() {
+					'"'
+				} else if self.parse('\'').is_ok() {
+					'\''
+				} else if self.parse('u').is_ok() {
+					self.expect("{")?;
+					let (digits, _) = self.parse(repeat(|c: char| c.is_ascii_hexdigit()))?;
+					self.expect("}")?;
+					match u32::from_str_radix(digits, 16).ok().and_then(std::char::from_u32) {
+						Some(c) => c,
+						None => return Err(Error {
+							i: escape_location,
+							msg: "invalid unicode escape sequence".to_string(),
+						}),
+					}
+				} else {
+					return Err(Error {
+						i: escape_location,
+						msg: "invalid escape sequence".to_string(),
+					});
+				};
+				s.push(c);
+			} else {
+				return Err(Error {
+					i: location,
+					msg: "unterminated string literal".to_string(),
+				});
+			}
+		}
+		Ok((s, location))
 	}
 	fn parse_type(&mut self) -> Result<(ast::Type<'a>, Location), Error> {
-		if let Ok((_, location)) = self.parse(keyword("number")) {
-			Ok((ast::Type::Number, location))
+		let (ty, location) = if let Ok((_, location)) = self.parse(keyword("number")) {
+			(ast::Type::Number, location)
 		} else if let Ok((_, location)) = self.parse(keyword("boolean")) {
-			Ok((ast::Type::Boolean, location))
+			(ast::Type::Boolean, location)
+		} else if let Ok((_, location)) = self.parse(keyword("string")) {
+			(ast::Type::String, location)
+		} else if let Ok((_, location)) = self.parse('(') {
+			// a function type like (number, number) => boolean
+			self.skip_comments()?;
+			let mut argument_types = Vec::new();
+			while self.parse(not(')')).is_ok() {
+				let (ty, _) = self.parse_type()?;
+				argument_types.push(ty);
+				self.skip_comments()?;
+				match self.parse(',') {
+					Ok(_) => {
+						self.skip_comments()?;
+						continue
+					}
+					Err(_) => break
+				}
+			}
+			self.expect(")")?;
+			self.skip_comments()?;
+			self.expect("=>")?;
+			self.skip_comments()?;
+			let return_type = if self.parse(keyword("void")).is_ok() {
+				ast::Type::Void
+			} else {
+				self.parse_type()?.0
+			};
+			(ast::Type::Function(argument_types, Box::new(return_type)), location)
 		} else if let Ok(_) = self.parse(peek(identifier_start_char)) {
 			let (s, location) = self.parse_identifier()?;
-			Ok((ast::Type::Class(s), location))
+			if self.type_parameters.contains(&s) {
+				(ast::Type::TypeParameter(s), location)
+			} else {
+				self.skip_comments()?;
+				let mut type_arguments = if self.parse('<').is_ok() {
+					self.skip_comments()?;
+					self.parse_type_arguments()?
+				} else {
+					Vec::new()
+				};
+				// Map is built in, with the wrong number of type arguments it is left to the type checker to report
+				if s == "Map" && type_arguments.len() == 2 {
+					let value_type = type_arguments.pop().unwrap();
+					let key_type = type_arguments.pop().unwrap();
+					(ast::Type::Map(Box::new(key_type), Box::new(value_type)), location)
+				} else {
+					(ast::Type::Class(s, type_arguments), location)
+				}
+			}
 		} else {
-			self.error("expected a type")
+			return self.error("expected a type");
+		};
+		// array and nullable suffixes like number[]? apply from left to right
+		let mut ty = ty;
+		loop {
+			if self.parse("[]").is_ok() {
+				ty = ast::Type::Array(Box::new(ty));
+			} else if self.parse('?').is_ok() {
+				ty = ast::Type::Nullable(Box::new(ty));
+			} else {
+				break;
+			}
+		}
+		Ok((ty, location))
+	}
+	// parses the beginning of a for of loop like let x of, for other for loops the cursor is restored
+	fn parse_for_of_variable(&mut self) -> Result<Option<(&'a str, bool)>, Error> {
+		let saved = self.cursor;
+		if let Ok((declaration, _)) = self.parse(choice!(keyword("let"), keyword("const"))) {
+			self.skip_comments()?;
+			if let Ok((name, _)) = self.parse_identifier() {
+				self.skip_comments()?;
+				if self.parse(keyword("of")).is_ok() {
+					return Ok(Some((name, declaration == "const")));
+				}
+			}
 		}
+		self.cursor = saved;
+		Ok(None)
 	}
 	fn parse_statement(&mut self) -> Result<ast::Statement<'a>, Error> {
-		if let Ok(_) = self.parse(keyword("let")) {
+		if let Ok((keyword, location)) = self.parse(choice!(keyword("let"), keyword("const"))) {
+			let constant = keyword == "const";
 			self.skip_comments()?;
 			let (name, _) = self.parse_identifier()?;
 			self.skip_comments()?;
-			self.expect("=")?;
-			self.skip_comments()?;
-			let expression = self.parse_expression(0)?;
-			self.skip_comments()?;
+			let ty = if self.parse(':').is_ok() {
+				self.skip_comments()?;
+				let (ty, _) = self.parse_type()?;
+				self.skip_comments()?;
+				Some(ty)
+			} else {
+				None
+			};
+			let expression = if self.parse('=').is_ok() {
+				self.skip_comments()?;
+				let expression = self.parse_expression(0)?;
+				self.skip_comments()?;
+				Some(expression)
+			} else if constant {
+				return self.error("expected an initializer");
+			} else if ty.is_some() {
+				None
+			} else {
+				return self.error("expected a type or an initializer");
+			};
 			self.expect(";")?;
+			self.locals.push(name);
 			Ok(ast::Statement::VariableDeclaration {
 				name,
+				ty,
 				expression,
+				constant,
+				location,
 			})
-		} else if let Ok(_) = self.parse(keyword("if")) {
+		} else if let Ok((_, location)) = self.parse(keyword("if")) {
 			self.skip_comments()?;
 			self.expect("(")?;
 			self.skip_comments()?;
@@ -315,8 +732,9 @@ impl <'a> Cursor<'a> {
 				condition,
 				statement,
 				else_statement,
+				location,
 			}))
-		} else if let Ok(_) = self.parse(keyword("while")) {
+		} else if let Ok((_, location)) = self.parse(keyword("while")) {
 			self.skip_comments()?;
 			self.expect("(")?;
 			self.skip_comments()?;
@@ -328,14 +746,126 @@ impl <'a> Cursor<'a> {
 			Ok(ast::Statement::While(ast::While {
 				condition,
 				statement,
+				location,
 			}))
-		} else if let Ok(_) = self.parse(keyword("return")) {
+		} else if let Ok((_, location)) = self.parse(keyword("do")) {
 			self.skip_comments()?;
-			let expression = self.parse_expression(0)?;
+			let statement = Box::new(self.parse_statement()?);
+			self.skip_comments()?;
+			self.parse(keyword("while")).set_error_message("expected while")?;
+			self.skip_comments()?;
+			self.expect("(")?;
+			self.skip_comments()?;
+			let condition = self.parse_expression(0)?;
+			self.skip_comments()?;
+			self.expect(")")?;
 			self.skip_comments()?;
 			self.expect(";")?;
-			Ok(ast::Statement::Return(expression))
+			Ok(ast::Statement::DoWhile(ast::DoWhile {
+				statement,
+				condition,
+				location,
+			}))
+		} else if let Ok((_, location)) = self.parse(keyword("for")) {
+			// the loop variables are only visible inside of the loop
+			let locals = self.locals.len();
+			self.skip_comments()?;
+			self.expect("(")?;
+			self.skip_comments()?;
+			if let Some((name, constant)) = self.parse_for_of_variable()? {
+				self.skip_comments()?;
+				let start = self.parse_expression(0)?;
+				self.skip_comments()?;
+				let iterable = if self.parse("..").is_ok() {
+					self.skip_comments()?;
+					let end = self.parse_expression(0)?;
+					self.skip_comments()?;
+					ast::Iterable::Range {
+						start,
+						end,
+					}
+				} else {
+					ast::Iterable::Array(start)
+				};
+				self.expect(")")?;
+				self.skip_comments()?;
+				self.locals.push(name);
+				let statement = Box::new(self.parse_statement()?);
+				self.locals.truncate(locals);
+				return Ok(ast::Statement::ForOf(ast::ForOf {
+					name,
+					constant,
+					iterable,
+					statement,
+					location,
+				}));
+			}
+			let initializer = if self.parse(';').is_ok() {
+				None
+			} else if self.parse(peek(choice!(keyword("let"), keyword("const")))).is_ok() {
+				Some(Box::new(self.parse_statement()?))
+			} else {
+				let expression = self.parse_expression(0)?;
+				self.skip_comments()?;
+				self.expect(";")?;
+				Some(Box::new(ast::Statement::Expression(expression)))
+			};
+			self.skip_comments()?;
+			let condition = if self.parse(peek(';')).is_ok() {
+				None
+			} else {
+				Some(self.parse_expression(0)?)
+			};
+			self.skip_comments()?;
+			self.expect(";")?;
+			self.skip_comments()?;
+			let step = if self.parse(peek(')')).is_ok() {
+				None
+			} else {
+				Some(self.parse_expression(0)?)
+			};
+			self.skip_comments()?;
+			self.expect(")")?;
+			self.skip_comments()?;
+			let statement = Box::new(self.parse_statement()?);
+			self.locals.truncate(locals);
+			Ok(ast::Statement::For(ast::For {
+				initializer,
+				condition,
+				step,
+				statement,
+				location,
+			}))
+		} else if let Ok((_, location)) = self.parse(keyword("break")) {
+			self.skip_comments()?;
+			let label = self.parse_label()?;
+			Ok(ast::Statement::Break {
+				label,
+				location,
+			})
+		} else if let Ok((_, location)) = self.parse(keyword("continue")) {
+			self.skip_comments()?;
+			let label = self.parse_label()?;
+			Ok(ast::Statement::Continue {
+				label,
+				location,
+			})
+		} else if let Ok((_, location)) = self.parse(keyword("return")) {
+			self.skip_comments()?;
+			let expression = if self.parse(';').is_ok() {
+				None
+			} else {
+				let expression = self.parse_expression(0)?;
+				self.skip_comments()?;
+				self.expect(";")?;
+				Some(expression)
+			};
+			Ok(ast::Statement::Return {
+				expression,
+				location,
+			})
 		} else if let Ok(_) = self.parse('{') {
+			let locals = self.locals.len();
 			self.skip_comments()?;
 			let mut statements = Vec::new();
 			while let Ok(_) = self.parse(not('}')) {
@@ -343,7 +873,19 @@ impl <'a> Cursor<'a> {
 				self.skip_comments()?;
 			}
 			self.expect("}")?;
+			self.locals.truncate(locals);
 			Ok(ast::Statement::Block(statements))
+		} else if self.parse(peek(sequence!(identifier_start_char, repeat(identifier_char), repeat(char::is_whitespace), ':'))).is_ok() {
+			let (label, location) = self.parse_identifier()?;
+			self.skip_comments()?;
+			self.expect(":")?;
+			self.skip_comments()?;
+			let statement = Box::new(self.parse_statement()?);
+			Ok(ast::Statement::Labeled {
+				label,
+				statement,
+				location,
+			})
 		} else {
 			let expression = self.parse_expression(0)?;
 			self.skip_comments()?;
@@ -351,38 +893,116 @@ impl <'a> Cursor<'a> {
 			Ok(ast::Statement::Expression(expression))
 		}
 	}
+	// parses the optional label of a break or continue statement including the semicolon
+	fn parse_label(&mut self) -> Result<Option<&'a str>, Error> {
+		if self.parse(';').is_ok() {
+			Ok(None)
+		} else {
+			let (label, _) = self.parse_identifier()?;
+			self.skip_comments()?;
+			self.expect(";")?;
+			Ok(Some(label))
+		}
+	}
 	fn parse_toplevel(&mut self) -> Result<(), Error> {
-		if let Ok(_) = self.parse(keyword("class")) {
+		if let Ok((s, _)) = self.parse(choice!(keyword("class"), sequence!(keyword("abstract"), repeat(char::is_whitespace), keyword("class")))) {
+			let is_abstract = s.starts_with("abstract");
 			self.skip_comments()?;
-			let (name, _) = self.parse_identifier()?;
+			let (name, location) = self.parse_identifier()?;
+			self.skip_comments()?;
+			let type_parameters = if self.parse('<').is_ok() {
+				self.skip_comments()?;
+				self.parse_type_parameters()?
+			} else {
+				Vec::new()
+			};
+			self.type_parameters = type_parameters.iter().map(|type_parameter| type_parameter.name).collect();
 			self.skip_comments()?;
+			let parent = if self.parse(keyword("extends")).is_ok() {
+				self.skip_comments()?;
+				let (parent, _) = self.parse_type()?;
+				self.skip_comments()?;
+				Some(parent)
+			} else {
+				None
+			};
+			let mut interfaces = Vec::new();
+			if self.parse(keyword("implements")).is_ok() {
+				self.skip_comments()?;
+				loop {
+					let (interface, _) = self.parse_identifier()?;
+					interfaces.push(interface);
+					self.skip_comments()?;
+					match self.parse(',') {
+						Ok(_) => self.skip_comments()?,
+						Err(_) => break,
+					}
+				}
+			}
 			self.expect("{")?;
 			self.skip_comments()?;
 			let mut fields = Vec::new();
 			let mut methods = Vec::new();
 			while let Ok(_) = self.parse(not('}')) {
-				if let Ok(_) = self.parse(keyword("constructor")) {
+				if self.parse(sequence!(keyword("readonly"), repeat(char::is_whitespace), peek(identifier_start_char))).is_ok() {
+					let (name, _) = self.parse_identifier()?;
+					self.skip_comments()?;
+					let (ty, initializer) = self.parse_field()?;
+					fields.push(ast::Field {
+						name,
+						ty,
+						initializer,
+						readonly: true,
+					});
+					self.skip_comments()?;
+				} else if self.parse(sequence!(keyword("abstract"), repeat(char::is_whitespace), peek(identifier_start_char))).is_ok() {
+					let (name, location) = self.parse_identifier()?;
+					if name == "constructor" {
+						return self.error("constructors cannot be abstract");
+					}
+					self.skip_comments()?;
+					self.expect("(")?;
+					let arguments = self.parse_arguments()?;
+					self.skip_comments()?;
+					let return_type = self.parse_return_type()?;
+					self.expect(";")?;
+					methods.push(crate::ast::Function {
+						name,
+						type_parameters: Vec::new(),
+						arguments,
+						return_type,
+						statements: Vec::new(),
+						location,
+						is_abstract: true,
+					});
+					self.skip_comments()?;
+				} else if let Ok((_, location)) = self.parse(keyword("constructor")) {
 					self.skip_comments()?;
 					self.expect("(")?;
 					let arguments = self.parse_arguments()?;
 					self.skip_comments()?;
 					self.expect("{")?;
 					self.skip_comments()?;
+					self.locals = arguments.iter().map(|(name, _)| *name).collect();
 					let mut statements = Vec::new();
 					while let Ok(_) = self.parse(not('}')) {
 						statements.push(self.parse_statement()?);
 						self.skip_comments()?;
 					}
 					self.expect("}")?;
+					self.locals.clear();
 					methods.push(crate::ast::Function {
 						name: "constructor",
+						type_parameters: Vec::new(),
 						arguments,
 						return_type: ast::Type::Void,
 						statements,
+						location,
+						is_abstract: false,
 					});
 					self.skip_comments()?;
 				} else if let Ok(_) = self.parse(peek(identifier_start_char)) {
-					let (name, _) = self.parse_identifier()?;
+					let (name, location) = self.parse_identifier()?;
 					self.skip_comments()?;
 					if let Ok(_) = self.parse('(') {
 						// method
@@ -391,26 +1011,32 @@ impl <'a> Cursor<'a> {
 						let return_type = self.parse_return_type()?;
 						self.expect("{")?;
 						self.skip_comments()?;
+						self.locals = arguments.iter().map(|(name, _)| *name).collect();
 						let mut statements = Vec::new();
 						while let Ok(_) = self.parse(not('}')) {
 							statements.push(self.parse_statement()?);
 							self.skip_comments()?;
 						}
 						self.expect("}")?;
+						self.locals.clear();
 						methods.push(crate::ast::Function {
 							name,
+							type_parameters: Vec::new(),
 							arguments,
 							return_type,
 							statements,
+							location,
+							is_abstract: false,
 						});
 					} else {
 						// field
-						self.expect(":")?;
-						self.skip_comments()?;
-						let (ty, _) = self.parse_type()?;
-						self.skip_comments()?;
-						self.expect(";")?;
-						fields.push((name, ty));
+						let (ty, initializer) = self.parse_field()?;
+						fields.push(ast::Field {
+							name,
+							ty,
+							initializer,
+							readonly: false,
+						});
 					}
 					self.skip_comments()?;
 				} else {
@@ -418,15 +1044,83 @@ impl <'a> Cursor<'a> {
 				}
 			}
 			self.expect("}")?;
+			self.type_parameters.clear();
 			self.program.classes.push(crate::ast::Class {
+				name,
+				type_parameters,
+				parent,
+				interfaces,
+				is_abstract,
+				fields,
+				methods,
+				location,
+			});
+			Ok(())
+		} else if self.parse(keyword("interface")).is_ok() {
+			self.skip_comments()?;
+			let (name, location) = self.parse_identifier()?;
+			self.skip_comments()?;
+			self.expect("{")?;
+			self.skip_comments()?;
+			let mut fields = Vec::new();
+			let mut methods = Vec::new();
+			while self.parse(not('}')).is_ok() {
+				let readonly = self.parse(sequence!(keyword("readonly"), repeat(char::is_whitespace), peek(identifier_start_char))).is_ok();
+				let (name, location) = self.parse_identifier()?;
+				self.skip_comments()?;
+				if self.parse('(').is_ok() {
+					// method signature
+					if readonly {
+						return self.error("methods cannot be readonly");
+					}
+					let arguments = self.parse_arguments()?;
+					self.skip_comments()?;
+					let return_type = self.parse_return_type()?;
+					self.expect(";")?;
+					methods.push(crate::ast::Function {
+						name,
+						type_parameters: Vec::new(),
+						arguments,
+						return_type,
+						statements: Vec::new(),
+						location,
+						is_abstract: true,
+					});
+				} else {
+					// field
+					self.expect(":")?;
+					self.skip_comments()?;
+					let (ty, _) = self.parse_type()?;
+					self.skip_comments()?;
+					self.expect(";")?;
+					fields.push(ast::Field {
+						name,
+						ty,
+						initializer: None,
+						readonly,
+					});
+				}
+				self.skip_comments()?;
+			}
+			self.expect("}")?;
+			self.program.interfaces.push(crate::ast::Interface {
 				name,
 				fields,
 				methods,
+				location,
 			});
 			Ok(())
 		} else if let Ok(_) = self.parse(keyword("function")) {
 			self.skip_comments()?;
-			let (name, _) = self.parse_identifier()?;
+			let (name, location) = self.parse_identifier()?;
+			self.skip_comments()?;
+			let type_parameters = if self.parse('<').is_ok() {
+				self.skip_comments()?;
+				self.parse_type_parameters()?
+			} else {
+				Vec::new()
+			};
+			self.type_parameters = type_parameters.iter().map(|type_parameter| type_parameter.name).collect();
 			self.skip_comments()?;
 			self.expect("(")?;
 			self.skip_comments()?;
@@ -435,23 +1129,73 @@ impl <'a> Cursor<'a> {
 			let return_type = self.parse_return_type()?;
 			self.expect("{")?;
 			self.skip_comments()?;
+			self.locals = arguments.iter().map(|(name, _)| *name).collect();
 			let mut statements = Vec::new();
 			while let Ok(_) = self.parse(not('}')) {
 				statements.push(self.parse_statement()?);
 				self.skip_comments()?;
 			}
 			self.expect("}")?;
+			self.locals.clear();
+			self.type_parameters.clear();
 			self.program.functions.push(crate::ast::Function {
 				name,
+				type_parameters,
 				arguments,
 				return_type,
 				statements,
+				location,
+				is_abstract: false,
 			});
 			Ok(())
 		} else {
 			self.error("expected a toplevel declaration")
 		}
 	}
+	fn parse_type_parameters(&mut self) -> Result<Vec<ast::TypeParameter<'a>>, Error> {
+		let mut type_parameters: Vec<ast::TypeParameter> = Vec::new();
+		loop {
+			let (name, _) = self.parse_identifier()?;
+			if type_parameters.iter().any(|type_parameter| type_parameter.name == name) {
+				return self.error(format!("type parameter \"{}\" already defined", name));
+			}
+			self.skip_comments()?;
+			let bound = if self.parse(keyword("extends")).is_ok() {
+				self.skip_comments()?;
+				let (bound, _) = self.parse_type()?;
+				self.skip_comments()?;
+				Some(bound)
+			} else {
+				None
+			};
+			type_parameters.push(ast::TypeParameter {
+				name,
+				bound,
+			});
+			match self.parse(',') {
+				Ok(_) => self.skip_comments()?,
+				Err(_) => break,
+			}
+		}
+		self.expect(">")?;
+		Ok(type_parameters)
+	}
+	fn parse_field(&mut self) -> Result<(ast::Type<'a>, Option<Box<Expression<'a>>>), Error> {
+		self.expect(":")?;
+		self.skip_comments()?;
+		let (ty, _) = self.parse_type()?;
+		self.skip_comments()?;
+		let initializer = if self.parse('=').is_ok() {
+			self.skip_comments()?;
+			let expression = self.parse_expression(0)?;
+			self.skip_comments()?;
+			Some(expression)
+		} else {
+			None
+		};
+		self.expect(";")?;
+		Ok((ty, initializer))
+	}
 	fn parse_arguments(&mut self) -> Result<Vec<(&'a str, ast::Type<'a>)>, Error> {
 		let mut arguments = Vec::new();
 		while let Ok(_) = self.parse(not(')')) {
@@ -517,10 +1261,13 @@ fn main() {
 			match parse_file(cursor) {
 				Ok(program) => {
 					match type_checker::type_check(&program) {
-						Ok(_) => {
+						Ok(output) => {
+							for warning in &output.warnings {
+								warning.print(file.as_str(), std::io::stderr().lock()).unwrap();
+							}
 							println!("{}", bold(green("type check successful")));
 							let mut printer = printer::Printer::new(std::io::stdout());
-							codegen::js::generate(&mut printer, &program);
+							codegen::js::generate(&mut printer, &program, &output.annotations).unwrap();
 						},
 						Err(e) => e.print(file.as_str(), std::io::stderr().lock()).unwrap(),
 					}
@@ -174,6 +174,7 @@ pub fn parse<'a, P: Parse>(mut p: P, s: &'a str) -> Option<(&'a str, &'a str)> {
 	p.parse(s).map(|i| s.split_at(i))
 }
 
+#[derive(Clone, Copy)]
 pub struct Cursor<'a> {
 	s: &'a str,
 	i: usize,
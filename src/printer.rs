@@ -39,6 +39,19 @@ pub fn green<T: Display>(t: T) -> impl Display {
 	Green(t)
 }
 
+struct Yellow<T>(T);
+
+impl <T: Display> Display for Yellow<T> {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "\x1B[33m{}\x1B[39m", self.0)?;
+		Ok(())
+	}
+}
+
+pub fn yellow<T: Display>(t: T) -> impl Display {
+	Yellow(t)
+}
+
 struct CommaSeparated<T>(T);
 
 impl <D: Display, T: IntoIterator<Item=D> + Clone> Display for CommaSeparated<T> {
@@ -77,15 +90,23 @@ impl<W: std::io::Write> Printer<W> {
 		writeln!(self.write, "{}", d)?;
 		Ok(())
 	}
+	// every line is indented, this way multi-line output like the body of a lambda lines up with the rest
+	pub fn println_lines<D: Display>(&mut self, d: D) -> std::io::Result<()> {
+		for line in d.to_string().lines() {
+			self.println(line)?;
+		}
+		Ok(())
+	}
 	pub fn increase_indentation(&mut self) {
 		self.indentation += 1;
 	}
 	pub fn decrease_indentation(&mut self) {
 		self.indentation -= 1;
 	}
-	pub fn indented<F: FnOnce(&mut Self)>(&mut self, mut f: F) {
+	pub fn indented<F: FnOnce(&mut Self) -> std::io::Result<()>>(&mut self, f: F) -> std::io::Result<()> {
 		self.indentation += 1;
-		f(self);
+		let result = f(self);
 		self.indentation -= 1;
+		result
 	}
 }
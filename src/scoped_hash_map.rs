@@ -33,6 +33,15 @@ impl <K: Hash + Eq, V> ScopedHashMap<K, V> {
 		}
 		None
 	}
+	pub fn get_mut<Q: Hash + Eq>(&mut self, k: &Q) -> Option<&mut V> where K: std::borrow::Borrow<Q> {
+		for scope in self.scopes.iter_mut().rev() {
+			match scope.get_mut(k) {
+				Some(v) => return Some(v),
+				None => continue,
+			}
+		}
+		None
+	}
 	pub fn insert(&mut self, k: K, v: V) -> Option<V> {
 		self.scopes.last_mut().unwrap().insert(k, v)
 	}
@@ -1,72 +1,753 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::scoped_hash_map::ScopedHashMap;
-use crate::error::{Error, Location};
-use crate::ast::Type;
+use crate::error::{Error, Warning, Location};
+use crate::ast::{Type, TypeParameter};
+use crate::control_flow::{ControlFlowGraph, BasicBlock, Node, Analysis};
+use crate::printer::comma_separated;
+
+struct Variable<'a> {
+	ty: Type<'a>,
+	constant: bool,
+	// the declaration of a variable that was declared without an initializer
+	uninitialized_declaration: Option<* const crate::ast::Statement<'a>>,
+	// a nullable variable that is known not to be null at the current position
+	non_null: bool,
+}
+
+impl <'a> Variable<'a> {
+	fn new(ty: Type<'a>) -> Self {
+		Variable {
+			ty,
+			constant: false,
+			uninitialized_declaration: None,
+			non_null: false,
+		}
+	}
+}
 
 struct Context<'a> {
-	variables: ScopedHashMap<&'a str, Type<'a>>,
+	variables: ScopedHashMap<&'a str, Variable<'a>>,
+	// maps names that refer to variables declared without an initializer to their declaration
+	bindings: HashMap<* const crate::ast::Expression<'a>, * const crate::ast::Statement<'a>>,
 	program: &'a crate::ast::Program<'a>,
+	return_type: Type<'a>,
+	// while the return type of a lambda is inferred, the common type of the values it returns so far
+	inferred_return_type: Option<Option<Type<'a>>>,
+	// readonly fields can only be assigned in constructors
+	in_constructor: bool,
+	// the labels of the enclosing loops
+	loops: Vec<Option<&'a str>>,
+	// the bounds of the type parameters that are in scope
+	bounds: HashMap<&'a str, Type<'a>>,
+	// the previous states of narrowed variables so that the narrowing can be undone at the end of a branch
+	narrowings: Vec<(&'a str, bool)>,
+	// the variables that are assigned by a lambda in the current function, they are never narrowed since the lambda can be called at any time
+	assigned_by_lambdas: HashSet<&'a str>,
+	annotations: HashMap<* const crate::ast::Expression<'a>, Annotation>,
+	warnings: Vec<Warning>,
+}
+
+pub struct Output<'a> {
+	pub warnings: Vec<Warning>,
+	pub annotations: HashMap<* const crate::ast::Expression<'a>, Annotation>,
 }
 
-pub fn type_check(program: &crate::ast::Program) -> Result<(), Error> {
+// expressions whose translation to JS depends on their types
+pub enum Annotation {
+	// a property access that refers to a method instead of a field, it has to be bound to its object
+	MethodReference,
+	// a call of a built-in method that returns undefined where the type checker expects null
+	UndefinedToNull,
+}
+
+// maps type parameters to type arguments
+type Substitution<'a> = HashMap<&'a str, Type<'a>>;
+
+impl <'a> Context<'a> {
+	fn get_binding(&self, expression: &crate::ast::Expression<'a>) -> Option<* const crate::ast::Statement<'a>> {
+		let key: * const crate::ast::Expression = expression;
+		self.bindings.get(&key).copied()
+	}
+}
+
+pub fn type_check<'a>(program: &'a crate::ast::Program<'a>) -> Result<Output<'a>, Error> {
 	let mut context = Context {
 		variables: ScopedHashMap::new(),
+		bindings: HashMap::new(),
 		program,
+		return_type: Type::Void,
+		inferred_return_type: None,
+		in_constructor: false,
+		loops: Vec::new(),
+		bounds: HashMap::new(),
+		narrowings: Vec::new(),
+		assigned_by_lambdas: HashSet::new(),
+		annotations: HashMap::new(),
+		warnings: Vec::new(),
 	};
+	for class in &program.classes {
+		if class.name == "Map" {
+			return error_at(class.location, "class \"Map\" already defined");
+		}
+		check_inheritance(&context, class)?;
+	}
+	for interface in &program.interfaces {
+		if interface.name == "Map" || program.get_class(interface.name).is_some() {
+			return error_at(interface.location, format!("class \"{}\" already defined", interface.name));
+		}
+		for field in &interface.fields {
+			check_type(&context, &field.ty, interface.location)?;
+		}
+		for method in &interface.methods {
+			check_signature_types(&context, method)?;
+		}
+	}
 	for function in &program.functions {
 		check_function(&mut context, function)?;
 	}
 	for class in &program.classes {
 		check_class(&mut context, class)?;
 	}
-	Ok(())
+	Ok(Output {
+		warnings: context.warnings,
+		annotations: context.annotations,
+	})
 }
 
 fn check_function<'a>(context: &mut Context<'a>, function: &crate::ast::Function<'a>) -> Result<(), Error> {
+	let bounds = context.bounds.clone();
+	add_bounds(context, &function.type_parameters, function.location)?;
+	check_signature_types(context, function)?;
+	context.return_type = function.return_type.clone();
+	context.in_constructor = function.name == "constructor";
 	context.variables.push_scope();
 	for (name, ty) in &function.arguments {
-		context.variables.insert(name, ty.clone());
+		context.variables.insert(name, Variable::new(ty.clone()));
+	}
+	let mut assigned_by_lambdas = HashSet::new();
+	for statement in &function.statements {
+		visit_expressions(statement, &mut |expression| collect_lambda_assignments(expression, &mut assigned_by_lambdas));
 	}
+	context.assigned_by_lambdas = assigned_by_lambdas;
+	let narrowings = context.narrowings.len();
 	for statement in &function.statements {
 		check_statement(context, statement)?;
 	}
+	end_branch(context, narrowings);
 	context.variables.pop_scope();
+	context.bounds = bounds;
+	check_control_flow(context, function)
+}
+
+fn check_signature_types<'a>(context: &Context<'a>, function: &crate::ast::Function<'a>) -> Result<(), Error> {
+	for (_, ty) in &function.arguments {
+		check_type(context, ty, function.location)?;
+	}
+	check_type(context, &function.return_type, function.location)
+}
+
+fn add_bounds<'a>(context: &mut Context<'a>, type_parameters: &[TypeParameter<'a>], location: Location) -> Result<(), Error> {
+	for type_parameter in type_parameters {
+		if let Some(bound) = &type_parameter.bound {
+			check_type(context, bound, location)?;
+			context.bounds.insert(type_parameter.name, bound.clone());
+		}
+	}
+	Ok(())
+}
+
+// makes sure that a type only refers to existing classes and interfaces and that their type arguments are valid
+fn check_type<'a>(context: &Context<'a>, ty: &Type<'a>, location: Location) -> Result<(), Error> {
+	if let Type::Nullable(ty) | Type::Array(ty) = ty {
+		return check_type(context, ty, location);
+	}
+	if let Type::Function(argument_types, return_type) = ty {
+		for ty in argument_types {
+			check_type(context, ty, location)?;
+		}
+		return check_type(context, return_type, location);
+	}
+	if let Type::Map(key_ty, value_ty) = ty {
+		check_type(context, key_ty, location)?;
+		return check_type(context, value_ty, location);
+	}
+	if let Type::Class(name, type_arguments) = ty {
+		for type_argument in type_arguments {
+			check_type(context, type_argument, location)?;
+		}
+		if *name == "Map" {
+			return error_at(location, format!("Map expects 2 type arguments but found {}", type_arguments.len()));
+		}
+		let type_parameters: &[TypeParameter] = match (context.program.get_class(name), context.program.get_interface(name)) {
+			(Some(class), _) => &class.type_parameters,
+			(None, Some(_)) => &[],
+			(None, None) => return error_at(location, format!("undefined class \"{}\"", name)),
+		};
+		if type_arguments.len() != type_parameters.len() {
+			return error_at(location, format!("{} expects {} type arguments but found {}", describe_class(context.program, name), type_parameters.len(), type_arguments.len()));
+		}
+		check_bounds(context, type_parameters, &bind_type_arguments(type_parameters, type_arguments), location)?;
+	}
+	Ok(())
+}
+
+fn check_bounds<'a>(context: &Context<'a>, type_parameters: &[TypeParameter<'a>], substitution: &Substitution<'a>, location: Location) -> Result<(), Error> {
+	for type_parameter in type_parameters {
+		if let (Some(bound), Some(ty)) = (&type_parameter.bound, substitution.get(type_parameter.name)) {
+			if !is_assignable(context, bound, ty) {
+				return error_at(location, format!("type argument {:?} does not satisfy the bound {:?} of type parameter \"{}\"", ty, bound, type_parameter.name));
+			}
+		}
+	}
+	Ok(())
+}
+
+fn check_control_flow<'a>(context: &mut Context<'a>, function: &crate::ast::Function<'a>) -> Result<(), Error> {
+	let graph = ControlFlowGraph::new(&function.statements);
+	check_return_paths(context, &graph, &function.statements, &function.return_type, function.location, &format!("\"{}\"", function.name))?;
+	graph.analyze(&DefiniteAssignment { context }, HashSet::new())?;
+	Ok(())
+}
+
+// makes sure that all paths return a value and warns about unreachable code
+fn check_return_paths<'a, 'b>(context: &mut Context<'a>, graph: &ControlFlowGraph<'a, 'b>, statements: &'b [crate::ast::Statement<'a>], return_type: &Type<'a>, location: Location, name: &str) -> Result<(), Error> {
+	let reachable = graph.reachable_blocks();
+	if *return_type != Type::Void && graph.falls_through(&reachable) {
+		return error_at(location, format!("not all paths of {} return a value", name));
+	}
+	check_reachability(context, graph, &reachable, statements);
+	Ok(())
+}
+
+// makes sure that variables declared without an initializer are assigned before they are read
+struct DefiniteAssignment<'a, 'c> {
+	context: &'c Context<'a>,
+}
+
+impl <'a, 'c> Analysis<'a> for DefiniteAssignment<'a, 'c> {
+	// the declarations that might still be unassigned
+	type State = HashSet<* const crate::ast::Statement<'a>>;
+	fn join(&self, a: &Self::State, b: &Self::State) -> Self::State {
+		a.union(b).copied().collect()
+	}
+	fn visit_expression(&self, expression: &crate::ast::Expression<'a>, unassigned: &mut Self::State) -> Result<bool, Error> {
+		use crate::ast::Expression::*;
+		match expression {
+			Name(s) => {
+				if let Some(declaration) = self.context.get_binding(expression) {
+					if unassigned.contains(&declaration) {
+						return error(self.context, expression, format!("variable \"{}\" is used before being assigned", s));
+					}
+				}
+				Ok(true)
+			},
+			Assign { name, expression } => {
+				match self.context.get_binding(name) {
+					Some(declaration) => {
+						self.walk_expression(expression, unassigned)?;
+						unassigned.remove(&declaration);
+						Ok(false)
+					},
+					None => Ok(true),
+				}
+			},
+			// variables that are captured by a lambda have to be assigned when the lambda is created
+			Lambda { body, .. } => {
+				ControlFlowGraph::new(std::slice::from_ref(&**body)).analyze(self, unassigned.clone())?;
+				Ok(false)
+			},
+			_ => Ok(true),
+		}
+	}
+	fn visit_statement(&self, statement: &crate::ast::Statement<'a>, unassigned: &mut Self::State) -> Result<(), Error> {
+		if let crate::ast::Statement::VariableDeclaration { expression: None, .. } = statement {
+			unassigned.insert(statement);
+		}
+		Ok(())
+	}
+}
+
+
+// warns about the first unreachable statement of every sequence of statements
+fn check_reachability<'a, 'b, I: IntoIterator<Item=&'b crate::ast::Statement<'a>>>(context: &mut Context<'a>, graph: &ControlFlowGraph<'a, 'b>, reachable: &[bool], statements: I) where 'a: 'b {
+	use crate::ast::{Statement::*, If, While, DoWhile, For, ForOf};
+	for statement in statements {
+		if !graph.is_reachable(reachable, statement) {
+			if let Some(i) = statement_location(context, statement) {
+				context.warnings.push(Warning {
+					i,
+					msg: "unreachable code".to_string(),
+				});
+				return;
+			}
+			continue;
+		}
+		match statement {
+			If(If{statement, else_statement, ..}) => {
+				check_reachability(context, graph, reachable, std::iter::once(&**statement));
+				if let Some(else_statement) = else_statement {
+					check_reachability(context, graph, reachable, std::iter::once(&**else_statement));
+				}
+			},
+			While(While{statement, ..}) | DoWhile(DoWhile{statement, ..}) | For(For{statement, ..}) | ForOf(ForOf{statement, ..}) | Labeled { statement, .. } => {
+				check_reachability(context, graph, reachable, std::iter::once(&**statement));
+			},
+			Block(statements) => {
+				check_reachability(context, graph, reachable, statements);
+			},
+			_ => {},
+		}
+	}
+}
+
+fn statement_location(context: &Context, statement: &crate::ast::Statement) -> Option<Location> {
+	use crate::ast::{Statement::*, If, While, DoWhile, For, ForOf};
+	match statement {
+		VariableDeclaration { location, .. } => Some(*location),
+		If(If{location, ..}) => Some(*location),
+		While(While{location, ..}) => Some(*location),
+		DoWhile(DoWhile{location, ..}) => Some(*location),
+		For(For{location, ..}) => Some(*location),
+		ForOf(ForOf{location, ..}) => Some(*location),
+		Break { location, .. } => Some(*location),
+		Continue { location, .. } => Some(*location),
+		Labeled { location, .. } => Some(*location),
+		Return { location, .. } => Some(*location),
+		Expression(expression) => {
+			let key: * const crate::ast::Expression = &**expression;
+			context.program.locations.get(&key).copied()
+		},
+		Block(statements) => statements.iter().find_map(|statement| statement_location(context, statement)),
+	}
+}
+
+// makes sure that the parent class exists and that a class does not inherit from itself, this has to happen before any inherited lookups
+fn check_inheritance(context: &Context, class: &crate::ast::Class) -> Result<(), Error> {
+	match &class.parent {
+		Some(Type::Class(parent, _)) if context.program.get_class(parent).is_none() => {
+			return error_at(class.location, format!("undefined class \"{}\"", parent));
+		},
+		Some(Type::Class(_, _)) => {},
+		Some(_) => return error_at(class.location, "a class can only extend another class"),
+		None => {},
+	}
+	if class.ancestors(context.program).skip(1).take(context.program.classes.len()).any(|ancestor| std::ptr::eq(ancestor, class)) {
+		return error_at(class.location, format!("class \"{}\" inherits from itself", class.name));
+	}
 	Ok(())
 }
 
 fn check_class<'a>(context: &mut Context<'a>, class: &crate::ast::Class<'a>) -> Result<(), Error> {
+	add_bounds(context, &class.type_parameters, class.location)?;
+	if let Some(parent) = &class.parent {
+		check_type(context, parent, class.location)?;
+	}
+	for field in &class.fields {
+		check_type(context, &field.ty, class.location)?;
+	}
+	if !class.is_abstract {
+		check_abstract_methods(context, class)?;
+	}
+	if let Some(parent) = class.get_parent(context.program) {
+		check_overrides(context, class, parent)?;
+	}
+	// inside of the class its type parameters are used as the type arguments
+	let this_ty = Type::Class(class.name, class.type_parameters.iter().map(|type_parameter| Type::TypeParameter(type_parameter.name)).collect());
+	for interface in &class.interfaces {
+		match context.program.get_interface(interface) {
+			Some(i) => {
+				if let Err(msg) = check_conformance(context, &this_ty, i, &mut Vec::new()) {
+					return error_at(class.location, format!("class \"{}\" does not implement interface \"{}\": {}", class.name, interface, msg));
+				}
+			},
+			None => return error_at(class.location, format!("undefined interface \"{}\"", interface)),
+		}
+	}
 	context.variables.push_scope();
-	context.variables.insert("this", Type::Class(class.name));
+	context.variables.insert("this", Variable::new(this_ty));
+	context.in_constructor = false;
+	for field in &class.fields {
+		if let Some(initializer) = &field.initializer {
+			let mut assigned_by_lambdas = HashSet::new();
+			visit_subexpressions(initializer, &mut |expression| collect_lambda_assignments(expression, &mut assigned_by_lambdas));
+			context.assigned_by_lambdas = assigned_by_lambdas;
+			assert_type(context, initializer, field.ty.clone())?;
+		}
+	}
 	for method in &class.methods {
-		check_function(context, method)?;
+		if !method.is_abstract {
+			check_function(context, method)?;
+		}
 	}
 	context.variables.pop_scope();
+	context.bounds.clear();
+	check_field_initialization(context, class)
+}
+
+// makes sure that a class that is not abstract implements all inherited abstract methods
+fn check_abstract_methods(context: &Context, class: &crate::ast::Class) -> Result<(), Error> {
+	for method in &class.methods {
+		if method.is_abstract {
+			return error_at(method.location, format!("abstract method \"{}\" can only be declared in an abstract class", method.name));
+		}
+	}
+	for ancestor in class.ancestors(context.program) {
+		for method in ancestor.methods.iter().filter(|method| method.is_abstract) {
+			if class.get_method(context.program, method.name).is_some_and(|method| method.is_abstract) {
+				return error_at(class.location, format!("class \"{}\" does not implement abstract method \"{}\" of class \"{}\"", class.name, method.name, ancestor.name));
+			}
+		}
+	}
+	Ok(())
+}
+
+fn check_overrides<'a>(context: &Context<'a>, class: &crate::ast::Class<'a>, parent: &crate::ast::Class<'a>) -> Result<(), Error> {
+	for field in &class.fields {
+		if let Some(ancestor) = parent.ancestors(context.program).find(|ancestor| ancestor.get_own_field(field.name).is_some()) {
+			return error_at(class.location, format!("field \"{}\" is already defined in class \"{}\"", field.name, ancestor.name));
+		}
+	}
+	for method in &class.methods {
+		if method.name == "constructor" {
+			continue;
+		}
+		if let Some(ancestor) = parent.ancestors(context.program).find(|ancestor| ancestor.get_own_field(method.name).is_some()) {
+			return error_at(method.location, format!("method \"{}\" conflicts with a field of class \"{}\"", method.name, ancestor.name));
+		}
+		if let Some(Type::Class(_, type_arguments)) = &class.parent {
+			if let Some((overridden, substitution)) = get_method(context.program, parent.name, type_arguments, method.name) {
+				if let Err(msg) = check_signature(context, method, &HashMap::new(), overridden, &substitution, "the method it overrides", &mut Vec::new()) {
+					return error_at(method.location, msg);
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+// checks that a method can be used in place of another one: its arguments must accept everything the other method accepts and its return value must be usable wherever the other one is
+fn check_signature<'a>(context: &Context<'a>, method: &crate::ast::Function<'a>, substitution: &Substitution<'a>, expected: &crate::ast::Function<'a>, expected_substitution: &Substitution<'a>, origin: &str, assumptions: &mut Vec<(Type<'a>, &'a str)>) -> Result<(), String> {
+	if method.arguments.len() != expected.arguments.len() {
+		return Err(format!("method \"{}\" must take {} arguments like {}", method.name, expected.arguments.len(), origin));
+	}
+	for ((name, ty), (_, expected_ty)) in method.arguments.iter().zip(&expected.arguments) {
+		let expected_ty = substitute(expected_ty, expected_substitution);
+		if !is_subtype(context, &substitute(ty, substitution), &expected_ty, assumptions) {
+			return Err(format!("argument \"{}\" of method \"{}\" must accept a {:?} like {}", name, method.name, expected_ty, origin));
+		}
+	}
+	let expected_return_type = substitute(&expected.return_type, expected_substitution);
+	if !is_subtype(context, &expected_return_type, &substitute(&method.return_type, substitution), assumptions) {
+		return Err(format!("method \"{}\" must return a {:?} like {}", method.name, expected_return_type, origin));
+	}
+	Ok(())
+}
+
+// checks that a class or an interface has all the fields and methods of an interface
+fn check_conformance<'a>(context: &Context<'a>, ty: &Type<'a>, interface: &crate::ast::Interface<'a>, assumptions: &mut Vec<(Type<'a>, &'a str)>) -> Result<(), String> {
+	let (name, type_arguments) = match ty {
+		Type::Class(name, type_arguments) => (name, type_arguments),
+		_ => return Err(format!("{:?} is not a class", ty)),
+	};
+	for field in &interface.fields {
+		match get_field(context.program, name, type_arguments, field.name) {
+			Some((actual, substitution)) => {
+				let actual_ty = substitute(&actual.ty, &substitution);
+				// fields that can be assigned through the interface must have exactly the same type
+				if field.readonly {
+					if !is_subtype(context, &field.ty, &actual_ty, assumptions) {
+						return Err(format!("field \"{}\" must have type {:?}", field.name, field.ty));
+					}
+				} else if actual_ty != field.ty {
+					return Err(format!("field \"{}\" must have type {:?}", field.name, field.ty));
+				} else if actual.readonly {
+					return Err(format!("field \"{}\" must not be readonly", field.name));
+				}
+			},
+			None => return Err(format!("missing field \"{}\"", field.name)),
+		}
+	}
+	for method in &interface.methods {
+		match get_method(context.program, name, type_arguments, method.name) {
+			Some((actual, substitution)) => check_signature(context, actual, &substitution, method, &HashMap::new(), "the method in the interface", assumptions)?,
+			None => return Err(format!("missing method \"{}\"", method.name)),
+		}
+	}
+	Ok(())
+}
+
+fn check_field_initialization<'a>(context: &Context<'a>, class: &crate::ast::Class<'a>) -> Result<(), Error> {
+	let analysis = FieldInitialization {
+		context,
+		class,
+	};
+	// field initializers run in order before the constructor, or right after the call to super in a derived class
+	let mut state = FieldState {
+		unassigned: class.fields.iter().map(|field| field.name).collect(),
+		before_super: false,
+		after_super: false,
+	};
+	for field in &class.fields {
+		if let Some(initializer) = &field.initializer {
+			analysis.walk_expression(initializer, &mut state)?;
+			state.unassigned.remove(field.name);
+		}
+	}
+	match class.get_own_method("constructor") {
+		Some(constructor) => {
+			state.before_super = class.parent.is_some();
+			let graph = ControlFlowGraph::new(&constructor.statements);
+			graph.analyze(&analysis, state)?;
+		},
+		None => {
+			if !state.unassigned.is_empty() {
+				return error_at(class.location, format!("{} without a constructor", describe_fields(class, &state.unassigned, "not initialized in a class")));
+			}
+		},
+	}
 	Ok(())
 }
 
+// makes sure that a constructor assigns all fields before this is used or the constructor returns
+struct FieldInitialization<'a, 'c> {
+	context: &'c Context<'a>,
+	class: &'c crate::ast::Class<'a>,
+}
+
+#[derive(Clone, PartialEq)]
+struct FieldState<'a> {
+	// the fields that might still be unassigned
+	unassigned: HashSet<&'a str>,
+	// in the constructor of a derived class, whether super might not have been called yet and whether it might have been called already
+	before_super: bool,
+	after_super: bool,
+}
+
+impl <'a, 'c> FieldInitialization<'a, 'c> {
+	fn check_super_called(&self, expression: &crate::ast::Expression<'a>, state: &FieldState<'a>) -> Result<(), Error> {
+		if state.before_super {
+			return error(self.context, expression, "cannot use this before super is called");
+		}
+		Ok(())
+	}
+}
+
+impl <'a, 'c> Analysis<'a> for FieldInitialization<'a, 'c> {
+	type State = FieldState<'a>;
+	fn join(&self, a: &Self::State, b: &Self::State) -> Self::State {
+		FieldState {
+			unassigned: a.unassigned.union(&b.unassigned).copied().collect(),
+			before_super: a.before_super || b.before_super,
+			after_super: a.after_super || b.after_super,
+		}
+	}
+	fn visit_expression(&self, expression: &crate::ast::Expression<'a>, state: &mut Self::State) -> Result<bool, Error> {
+		use crate::ast::Expression::*;
+		match expression {
+			PropertyAccess { object, property } if matches!(**object, This) => {
+				self.check_super_called(expression, state)?;
+				// a reference to a method is bound to this and the method can be called through it right away
+				if let Some(Annotation::MethodReference) = self.context.annotations.get(&(expression as * const crate::ast::Expression)) {
+					if !state.unassigned.is_empty() {
+						return error(self.context, expression, format!("cannot reference method \"{}\" because {}", property, describe_fields(self.class, &state.unassigned, "not assigned yet")));
+					}
+				} else if state.unassigned.contains(property) {
+					return error(self.context, expression, format!("field \"{}\" is used before being assigned", property));
+				}
+				Ok(false)
+			},
+			Assign { name, expression } => {
+				match &**name {
+					PropertyAccess { object, property } if matches!(**object, This) => {
+						self.walk_expression(expression, state)?;
+						self.check_super_called(name, state)?;
+						state.unassigned.remove(property);
+						Ok(false)
+					},
+					_ => Ok(true),
+				}
+			},
+			MethodCall { object, method, arguments } if matches!(**object, This) => {
+				for argument in arguments {
+					self.walk_expression(argument, state)?;
+				}
+				self.check_super_called(expression, state)?;
+				if !state.unassigned.is_empty() {
+					return error(self.context, expression, format!("cannot call method \"{}\" because {}", method, describe_fields(self.class, &state.unassigned, "not assigned yet")));
+				}
+				Ok(false)
+			},
+			SuperMethodCall { method, arguments } => {
+				for argument in arguments {
+					self.walk_expression(argument, state)?;
+				}
+				self.check_super_called(expression, state)?;
+				if !state.unassigned.is_empty() {
+					return error(self.context, expression, format!("cannot call method \"{}\" because {}", method, describe_fields(self.class, &state.unassigned, "not assigned yet")));
+				}
+				Ok(false)
+			},
+			SuperCall { arguments } => {
+				for argument in arguments {
+					self.walk_expression(argument, state)?;
+				}
+				if state.after_super {
+					return error(self.context, expression, "super might already have been called");
+				}
+				state.before_super = false;
+				state.after_super = true;
+				Ok(false)
+			},
+			This => {
+				self.check_super_called(expression, state)?;
+				if !state.unassigned.is_empty() {
+					return error(self.context, expression, format!("cannot use this because {}", describe_fields(self.class, &state.unassigned, "not assigned yet")));
+				}
+				Ok(false)
+			},
+			// a lambda that captures this might be called before the remaining fields are assigned
+			Lambda { body, .. } => {
+				let mut captures_this = false;
+				visit_expressions(body, &mut |expression| captures_this |= matches!(expression, This | SuperMethodCall { .. }));
+				if captures_this {
+					self.check_super_called(expression, state)?;
+					if !state.unassigned.is_empty() {
+						return error(self.context, expression, format!("cannot capture this in a lambda because {}", describe_fields(self.class, &state.unassigned, "not assigned yet")));
+					}
+				}
+				Ok(false)
+			},
+			_ => Ok(true),
+		}
+	}
+	fn visit_exit(&self, block: &BasicBlock<'a, '_>, state: &Self::State) -> Result<(), Error> {
+		let location = match block.nodes.last() {
+			Some(Node::Statement(crate::ast::Statement::Return { location, .. })) => *location,
+			_ => self.class.get_own_method("constructor").unwrap().location,
+		};
+		if state.before_super {
+			return error_at(location, "super must be called before the constructor returns");
+		}
+		if !state.unassigned.is_empty() {
+			return error_at(location, format!("{} before the constructor returns", describe_fields(self.class, &state.unassigned, "not assigned")));
+		}
+		Ok(())
+	}
+}
+
+// lists the given fields in the order of their declaration
+fn describe_fields(class: &crate::ast::Class, fields: &HashSet<&str>, state: &str) -> String {
+	let names: Vec<String> = class.fields.iter().filter(|field| fields.contains(field.name)).map(|field| format!("\"{}\"", field.name)).collect();
+	if names.len() == 1 {
+		format!("field {} is {}", names[0], state)
+	} else {
+		format!("fields {} are {}", comma_separated(names.iter()), state)
+	}
+}
+
 fn check_statement<'a>(context: &mut Context<'a>, statement: &crate::ast::Statement<'a>) -> Result<(), Error> {
-	use crate::ast::{Statement::*, If, While};
+	use crate::ast::{Statement::*, If};
 	match statement {
-		VariableDeclaration { name, expression } => {
+		VariableDeclaration { name, ty, expression, constant, location } => {
 			if let Some(_) = context.variables.get_local(name) {
-				return error(context, expression, format!("variable \"{}\" already defined", name));
+				return error_at(*location, format!("variable \"{}\" already defined", name));
+			}
+			// a nullable variable that is initialized with a value that is not null is narrowed
+			let mut non_null = false;
+			let mut variable = match (ty, expression) {
+				(Some(ty), Some(expression)) => {
+					check_type(context, ty, *location)?;
+					let value_ty = assert_type(context, expression, ty.clone())?;
+					non_null = !is_nullable(&value_ty);
+					Variable::new(ty.clone())
+				},
+				(Some(ty), None) => {
+					check_type(context, ty, *location)?;
+					Variable {
+						uninitialized_declaration: Some(statement),
+						..Variable::new(ty.clone())
+					}
+				},
+				(None, Some(expression)) => {
+					let ty = check_expression(context, expression)?;
+					if ty == Type::Null {
+						return error(context, expression, "cannot infer the type of null, the variable needs a type annotation");
+					}
+					Variable::new(ty)
+				},
+				(None, None) => unreachable!(),
+			};
+			variable.constant = *constant;
+			context.variables.insert(name, variable);
+			if non_null {
+				narrow(context, vec![name]);
 			}
-			let ty = check_expression(context, expression)?;
-			context.variables.insert(name, ty.clone());
 		},
-		If(If{condition, statement, else_statement}) => {
+		If(If{condition, statement, else_statement, ..}) => {
 			assert_type(context, condition, Type::Boolean)?;
+			let narrowings = context.narrowings.len();
+			narrow(context, non_null_variables(condition, true));
 			check_statement(context, statement)?;
+			end_branch(context, narrowings);
 			if let Some(else_statement) = else_statement {
+				narrow(context, non_null_variables(condition, false));
 				check_statement(context, else_statement)?;
+				end_branch(context, narrowings);
+			}
+			// after an if statement that exits when a variable is null the variable is known not to be null
+			if always_exits(statement) {
+				narrow(context, non_null_variables(condition, false));
+			}
+			if else_statement.as_ref().is_some_and(|else_statement| always_exits(else_statement)) {
+				narrow(context, non_null_variables(condition, true));
 			}
 		},
-		While(While{condition, statement}) => {
-			assert_type(context, condition, Type::Boolean)?;
-			check_statement(context, statement)?;
+		While(_) | DoWhile(_) | For(_) | ForOf(_) => {
+			check_loop(context, statement, None)?;
 		},
-		Return(expression) => {
-			check_expression(context, expression)?;
+		Break { label, location } => {
+			check_jump(context, "break", *label, *location)?;
+		},
+		Continue { label, location } => {
+			check_jump(context, "continue", *label, *location)?;
+		},
+		Labeled { label, statement, location } => {
+			if context.loops.contains(&Some(label)) {
+				return error_at(*location, format!("label \"{}\" already defined", label));
+			}
+			match **statement {
+				While(_) | DoWhile(_) | For(_) | ForOf(_) => check_loop(context, statement, Some(label))?,
+				_ => return error_at(*location, "only loops can be labeled"),
+			}
+		},
+		Return { expression, location } if context.inferred_return_type.is_some() => {
+			let ty = match expression {
+				Some(expression) => check_expression(context, expression)?,
+				None => Type::Void,
+			};
+			let ty = match context.inferred_return_type.take().flatten() {
+				Some(inferred) => match common_type(context, &inferred, &ty) {
+					Some(ty) => ty,
+					None if inferred == Type::Void || ty == Type::Void => return error_at(*location, "a lambda without a return type must either always or never return a value"),
+					None => return error_at(*location, format!("cannot infer the return type of a lambda that returns a {:?} and a {:?}", inferred, ty)),
+				},
+				None => ty,
+			};
+			context.inferred_return_type = Some(Some(ty));
+		},
+		Return { expression, location } => {
+			match (expression, context.return_type.clone()) {
+				(Some(expression), Type::Void) => {
+					return error(context, expression, "cannot return a value from a function without a return type");
+				},
+				(Some(expression), return_type) => {
+					assert_type(context, expression, return_type)?;
+				},
+				(None, Type::Void) => {},
+				(None, return_type) => {
+					return error_at(*location, format!("expected a return value of type {:?}", return_type));
+				},
+			}
 		},
 		Expression(expression) => {
 			check_expression(context, expression)?;
@@ -82,146 +763,1105 @@ fn check_statement<'a>(context: &mut Context<'a>, statement: &crate::ast::Statem
 	Ok(())
 }
 
+// whether a statement never completes normally
+fn always_exits(statement: &crate::ast::Statement) -> bool {
+	use crate::ast::{Statement::*, If};
+	match statement {
+		Return { .. } | Break { .. } | Continue { .. } => true,
+		Block(statements) => statements.iter().any(always_exits),
+		If(If{statement, else_statement: Some(else_statement), ..}) => always_exits(statement) && always_exits(else_statement),
+		_ => false,
+	}
+}
+
+// the variables that are known not to be null when a condition has the given value
+fn non_null_variables<'a>(condition: &crate::ast::Expression<'a>, value: bool) -> Vec<&'a str> {
+	use crate::ast::Expression::*;
+	use crate::ast::{RelationalOperation, LogicalOperation};
+	match condition {
+		RelationalExpression(e) => {
+			let name = match (&*e.left, &*e.right) {
+				(Name(name), Null) | (Null, Name(name)) => *name,
+				_ => return Vec::new(),
+			};
+			match (&e.operation, value) {
+				(RelationalOperation::NotEqual, true) | (RelationalOperation::Equal, false) => vec![name],
+				_ => Vec::new(),
+			}
+		},
+		LogicalExpression(e) => {
+			match (&e.operation, value) {
+				(LogicalOperation::And, true) | (LogicalOperation::Or, false) => {
+					let mut names = non_null_variables(&e.left, value);
+					// a variable that is assigned in the right operand might be null again
+					let mut assigned = Vec::new();
+					visit_subexpressions(&e.right, &mut |expression| assigned.extend(assigned_variable(expression)));
+					names.retain(|name| !assigned.contains(name));
+					names.extend(non_null_variables(&e.right, value));
+					names
+				},
+				_ => Vec::new(),
+			}
+		},
+		Not(e) => non_null_variables(e, !value),
+		_ => Vec::new(),
+	}
+}
+
+// marks variables as not null until the end of the current branch
+fn narrow<'a>(context: &mut Context<'a>, names: Vec<&'a str>) {
+	for name in names {
+		if context.assigned_by_lambdas.contains(name) {
+			continue;
+		}
+		if let Some(variable) = context.variables.get_mut(&name) {
+			context.narrowings.push((name, variable.non_null));
+			variable.non_null = true;
+		}
+	}
+}
+
+// undoes the narrowings since the start of a branch
+fn end_branch<'a>(context: &mut Context<'a>, start: usize) {
+	while context.narrowings.len() > start {
+		let (name, non_null) = context.narrowings.pop().unwrap();
+		if let Some(variable) = context.variables.get_mut(&name) {
+			// a variable that was assigned null in the meantime stays nullable
+			variable.non_null &= non_null;
+		}
+	}
+}
+
+// forgets what is known about variables that are assigned inside of a statement
+// this is necessary for loops since they return to an earlier position and for lambdas since they can be called at any time
+fn forget_assigned_variables<'a>(context: &mut Context<'a>, statement: &crate::ast::Statement<'a>) {
+	let mut names = Vec::new();
+	visit_expressions(statement, &mut |expression| names.extend(assigned_variable(expression)));
+	for name in names {
+		if let Some(variable) = context.variables.get_mut(&name) {
+			variable.non_null = false;
+		}
+	}
+}
+
+// the variable that an expression assigns to, if any
+fn assigned_variable<'a>(expression: &crate::ast::Expression<'a>) -> Option<&'a str> {
+	use crate::ast::Expression::*;
+	match expression {
+		Assign { name, .. } | CompoundAssign { name, .. } => match **name {
+			Name(name) => Some(name),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+// collects the variables that are assigned inside of the body of a lambda
+fn collect_lambda_assignments<'a>(expression: &crate::ast::Expression<'a>, names: &mut HashSet<&'a str>) {
+	if let crate::ast::Expression::Lambda { body, .. } = expression {
+		visit_expressions(body, &mut |expression| names.extend(assigned_variable(expression)));
+	}
+}
+
+// calls f for every expression inside of a statement, including the bodies of lambdas
+fn visit_expressions<'a, F: FnMut(&crate::ast::Expression<'a>)>(statement: &crate::ast::Statement<'a>, f: &mut F) {
+	use crate::ast::{Statement::*, If, While, DoWhile, For, ForOf};
+	match statement {
+		VariableDeclaration { expression, .. } | Return { expression, .. } => {
+			if let Some(expression) = expression {
+				visit_subexpressions(expression, f);
+			}
+		},
+		If(If{condition, statement, else_statement, ..}) => {
+			visit_subexpressions(condition, f);
+			visit_expressions(statement, f);
+			if let Some(else_statement) = else_statement {
+				visit_expressions(else_statement, f);
+			}
+		},
+		While(While{condition, statement, ..}) | DoWhile(DoWhile{condition, statement, ..}) => {
+			visit_subexpressions(condition, f);
+			visit_expressions(statement, f);
+		},
+		For(For{initializer, condition, step, statement, ..}) => {
+			if let Some(initializer) = initializer {
+				visit_expressions(initializer, f);
+			}
+			for expression in condition.iter().chain(step) {
+				visit_subexpressions(expression, f);
+			}
+			visit_expressions(statement, f);
+		},
+		ForOf(ForOf{iterable, statement, ..}) => {
+			match iterable {
+				crate::ast::Iterable::Array(array) => visit_subexpressions(array, f),
+				crate::ast::Iterable::Range { start, end } => {
+					visit_subexpressions(start, f);
+					visit_subexpressions(end, f);
+				},
+			}
+			visit_expressions(statement, f);
+		},
+		Break { .. } | Continue { .. } => {},
+		Labeled { statement, .. } => visit_expressions(statement, f),
+		Expression(expression) => visit_subexpressions(expression, f),
+		Block(statements) => {
+			for statement in statements {
+				visit_expressions(statement, f);
+			}
+		},
+	}
+}
+
+// calls f for an expression and all of its subexpressions
+fn visit_subexpressions<'a, F: FnMut(&crate::ast::Expression<'a>)>(expression: &crate::ast::Expression<'a>, f: &mut F) {
+	use crate::ast::Expression::*;
+	f(expression);
+	match expression {
+		Number(_) | Boolean(_) | String(_) | Null | Name(_) | This => {},
+		ArithmeticExpression(e) => {
+			visit_subexpressions(&e.left, f);
+			visit_subexpressions(&e.right, f);
+		},
+		RelationalExpression(e) => {
+			visit_subexpressions(&e.left, f);
+			visit_subexpressions(&e.right, f);
+		},
+		LogicalExpression(e) => {
+			visit_subexpressions(&e.left, f);
+			visit_subexpressions(&e.right, f);
+		},
+		Not(e) | Minus(e) | Plus(e) | PrefixIncrement(e) | PrefixDecrement(e) | PostfixIncrement(e) | PostfixDecrement(e) => {
+			visit_subexpressions(e, f);
+		},
+		Assign { name, expression } | CompoundAssign { name, expression, .. } => {
+			visit_subexpressions(name, f);
+			visit_subexpressions(expression, f);
+		},
+		Conditional { condition, then_expression, else_expression } => {
+			visit_subexpressions(condition, f);
+			visit_subexpressions(then_expression, f);
+			visit_subexpressions(else_expression, f);
+		},
+		PropertyAccess { object, .. } => visit_subexpressions(object, f),
+		MethodCall { object, arguments, .. } => {
+			visit_subexpressions(object, f);
+			for argument in arguments {
+				visit_subexpressions(argument, f);
+			}
+		},
+		Call { function, arguments, .. } => {
+			visit_subexpressions(function, f);
+			for argument in arguments {
+				visit_subexpressions(argument, f);
+			}
+		},
+		ClassInstantiation { arguments, .. } | SuperCall { arguments } | SuperMethodCall { arguments, .. } | Array(arguments) => {
+			for argument in arguments {
+				visit_subexpressions(argument, f);
+			}
+		},
+		Index { array, index } => {
+			visit_subexpressions(array, f);
+			visit_subexpressions(index, f);
+		},
+		Lambda { body, .. } => visit_expressions(body, f),
+	}
+}
+
+fn check_loop<'a>(context: &mut Context<'a>, statement: &crate::ast::Statement<'a>, label: Option<&'a str>) -> Result<(), Error> {
+	use crate::ast::{Statement, While, DoWhile, For, ForOf, Iterable};
+	forget_assigned_variables(context, statement);
+	match statement {
+		Statement::While(While{condition, statement, ..}) => {
+			assert_type(context, condition, Type::Boolean)?;
+			check_loop_body(context, statement, label, non_null_variables(condition, true))?;
+		},
+		Statement::DoWhile(DoWhile{statement, condition, ..}) => {
+			check_loop_body(context, statement, label, Vec::new())?;
+			assert_type(context, condition, Type::Boolean)?;
+		},
+		Statement::For(For{initializer, condition, step, statement, ..}) => {
+			// variables declared in the initializer are only visible inside the loop
+			context.variables.push_scope();
+			if let Some(initializer) = initializer {
+				check_statement(context, initializer)?;
+			}
+			if let Some(condition) = condition {
+				assert_type(context, condition, Type::Boolean)?;
+			}
+			if let Some(step) = step {
+				check_expression(context, step)?;
+			}
+			let non_null = condition.as_ref().map_or(Vec::new(), |condition| non_null_variables(condition, true));
+			check_loop_body(context, statement, label, non_null)?;
+			context.variables.pop_scope();
+		},
+		Statement::ForOf(ForOf{name, constant, iterable, statement, ..}) => {
+			let element_ty = match iterable {
+				Iterable::Array(array) => {
+					let ty = check_expression(context, array)?;
+					match resolve_bound(context, ty) {
+						Type::Array(element_ty) => *element_ty,
+						Type::Nullable(_) => return error(context, array, "trying to iterate over a value that might be null"),
+						ty => return error(context, array, format!("cannot iterate over a {:?}", ty)),
+					}
+				},
+				Iterable::Range { start, end } => {
+					assert_type(context, start, Type::Number)?;
+					assert_type(context, end, Type::Number)?;
+					Type::Number
+				},
+			};
+			// the loop variable is only visible inside the loop
+			context.variables.push_scope();
+			context.variables.insert(name, Variable {
+				constant: *constant,
+				..Variable::new(element_ty)
+			});
+			check_loop_body(context, statement, label, Vec::new())?;
+			context.variables.pop_scope();
+		},
+		_ => unreachable!(),
+	}
+	Ok(())
+}
+
+fn check_loop_body<'a>(context: &mut Context<'a>, statement: &crate::ast::Statement<'a>, label: Option<&'a str>, non_null: Vec<&'a str>) -> Result<(), Error> {
+	let narrowings = context.narrowings.len();
+	narrow(context, non_null);
+	context.loops.push(label);
+	let result = check_statement(context, statement);
+	context.loops.pop();
+	end_branch(context, narrowings);
+	result
+}
+
+fn check_jump(context: &Context, keyword: &str, label: Option<&str>, location: Location) -> Result<(), Error> {
+	match label {
+		Some(label) => {
+			if !context.loops.contains(&Some(label)) {
+				return error_at(location, format!("undefined label \"{}\"", label));
+			}
+		},
+		None => {
+			if context.loops.is_empty() {
+				return error_at(location, format!("{} outside of a loop", keyword));
+			}
+		},
+	}
+	Ok(())
+}
+
 fn check_expression<'a>(context: &mut Context<'a>, expression: &crate::ast::Expression<'a>) -> Result<Type<'a>, Error> {
 	use crate::ast::Expression::*;
 	match expression {
-		Number(s) => Ok(Type::Number),
+		Number(_) => Ok(Type::Number),
+		Boolean(_) => Ok(Type::Boolean),
+		String(_) => Ok(Type::String),
+		Null => Ok(Type::Null),
 		Name(s) => {
 			match context.variables.get(s) {
-				None => error(context, expression, format!("undefined variable \"{}\"", s)),
-				Some(ty) => Ok(ty.clone())
+				Some(variable) => {
+					if let Some(declaration) = variable.uninitialized_declaration {
+						context.bindings.insert(expression, declaration);
+					}
+					match &variable.ty {
+						Type::Nullable(ty) if variable.non_null => Ok((**ty).clone()),
+						ty => Ok(ty.clone()),
+					}
+				},
+				// functions can be used as values
+				None => match context.program.get_function(s) {
+					Some(f) if !f.type_parameters.is_empty() => error(context, expression, format!("generic function \"{}\" cannot be used as a value", s)),
+					Some(f) => Ok(function_type(f, &HashMap::new())),
+					None => error(context, expression, format!("undefined variable \"{}\"", s)),
+				},
 			}
 		},
 		ArithmeticExpression(expression) => {
-			assert_type(context, &*expression.left, Type::Number)?;
-			assert_type(context, &*expression.right, Type::Number)?;
-			Ok(Type::Number)
+			match expression.operation {
+				crate::ast::ArithmeticOperation::Add => {
+					// + either adds numbers or concatenates strings
+					let ty = check_expression(context, &expression.left)?;
+					match ty {
+						Type::Number | Type::String => {
+							assert_type(context, &expression.right, ty.clone())?;
+							Ok(ty)
+						},
+						_ => error(context, &expression.left, format!("type mismatch: expected a Number or a String but found a {:?}", ty)),
+					}
+				},
+				_ => {
+					assert_type(context, &expression.left, Type::Number)?;
+					assert_type(context, &expression.right, Type::Number)?;
+					Ok(Type::Number)
+				},
+			}
 		},
 		RelationalExpression(expression) => {
-			assert_type(context, &*expression.left, Type::Number)?;
-			assert_type(context, &*expression.right, Type::Number)?;
+			use crate::ast::RelationalOperation::*;
+			match expression.operation {
+				Equal | NotEqual => {
+					// any two values of the same type or of related classes can be compared, class instances are compared by reference
+					let left_ty = check_expression(context, &expression.left)?;
+					let right_ty = check_expression(context, &expression.right)?;
+					if left_ty == Type::Void {
+						return error(context, &expression.left, "cannot compare a value of type Void");
+					}
+					// variables that are known not to be null can still be compared with null
+					let is_null_check = |ty: &Type, other: &crate::ast::Expression| *ty == Type::Null && is_nullable_variable(context, other);
+					if is_null_check(&left_ty, &expression.right) || is_null_check(&right_ty, &expression.left) {
+						return Ok(Type::Boolean);
+					}
+					if !is_assignable(context, &left_ty, &right_ty) && !is_assignable(context, &right_ty, &left_ty) {
+						return error(context, &expression.right, format!("cannot compare a {:?} with a {:?}", left_ty, right_ty));
+					}
+				},
+				LessThan | LessThanOrEqual | GreaterThan | GreaterThanOrEqual => {
+					assert_type(context, &expression.left, Type::Number)?;
+					assert_type(context, &expression.right, Type::Number)?;
+				},
+			}
 			Ok(Type::Boolean)
 		},
 		LogicalExpression(expression) => {
 			assert_type(context, &*expression.left, Type::Boolean)?;
+			// the right operand is only evaluated if the left one is true for && and false for ||
+			let value = matches!(expression.operation, crate::ast::LogicalOperation::And);
+			let narrowings = context.narrowings.len();
+			narrow(context, non_null_variables(&expression.left, value));
 			assert_type(context, &*expression.right, Type::Boolean)?;
+			end_branch(context, narrowings);
 			Ok(Type::Boolean)
 		},
 		Not(expression) => {
 			assert_type(context, &*expression, Type::Boolean)?;
 			Ok(Type::Boolean)
 		},
+		Minus(expression) | Plus(expression) => {
+			assert_type(context, expression, Type::Number)?;
+			Ok(Type::Number)
+		},
 		Assign { name, expression } => {
-			match **name {
-				Name(_) | PropertyAccess {..} => {
-					let ty = check_expression(context, name)?;
-					assert_type(context, expression, ty.clone())?;
-					Ok(ty)
-				},
-				_ => error(context, name, "left hand of an assignment must be a name or a property access"),
-			}
-		},
-		Call { function, arguments } => {
-			match **function {
-				Name(s) => {
-					match context.program.get_function(s) {
-						Some(f) => {
-							check_arguments(context, function, f, arguments)?;
-							Ok(f.return_type.clone())
-						},
+			let mut ty = check_assignment_target(context, name, "left hand of an assignment must be a name, a property access or an index expression")?;
+			// a variable that is known not to be null can still be assigned null
+			if let crate::ast::Expression::Name(s) = **name {
+				ty = context.variables.get(&s).map_or(ty, |variable| variable.ty.clone());
+			}
+			let value_ty = assert_type(context, expression, ty.clone())?;
+			// assigning a value that is not null to a nullable variable narrows it
+			if let crate::ast::Expression::Name(s) = **name {
+				if !is_nullable(&value_ty) {
+					narrow(context, vec![s]);
+				} else if let Some(variable) = context.variables.get_mut(&s) {
+					variable.non_null = false;
+				}
+			}
+			Ok(ty)
+		},
+		CompoundAssign { operation, name, expression } => {
+			use crate::ast::AssignOperation::*;
+			let ty = check_assignment_target(context, name, "left hand of an assignment must be a name, a property access or an index expression")?;
+			let valid = match operation {
+				Add => ty == Type::Number || ty == Type::String,
+				Subtract | Multiply | Divide | Remainder => ty == Type::Number,
+				And | Or => ty == Type::Boolean,
+			};
+			if !valid {
+				return error(context, name, format!("invalid compound assignment to a {:?}", ty));
+			}
+			assert_type(context, expression, ty.clone())?;
+			Ok(ty)
+		},
+		PrefixIncrement(expression) | PrefixDecrement(expression) | PostfixIncrement(expression) | PostfixDecrement(expression) => {
+			let ty = check_assignment_target(context, expression, "operand of an increment or decrement must be a name, a property access or an index expression")?;
+			if ty != Type::Number {
+				return error(context, expression, format!("type mismatch: expected a Number but found a {:?}", ty));
+			}
+			Ok(ty)
+		},
+		Conditional { condition, then_expression, else_expression } => {
+			assert_type(context, condition, Type::Boolean)?;
+			let narrowings = context.narrowings.len();
+			narrow(context, non_null_variables(condition, true));
+			let then_ty = check_expression(context, then_expression)?;
+			end_branch(context, narrowings);
+			narrow(context, non_null_variables(condition, false));
+			let else_ty = check_expression(context, else_expression)?;
+			end_branch(context, narrowings);
+			match common_type(context, &then_ty, &else_ty) {
+				Some(ty) => Ok(ty),
+				None => error(context, expression, format!("branches of a conditional expression must have the same type but found a {:?} and a {:?}", then_ty, else_ty)),
+			}
+		},
+		Call { function, type_arguments, arguments } => {
+			// local variables shadow functions
+			if let Name(s) = **function {
+				if context.variables.get(&s).is_none() {
+					return match context.program.get_function(s) {
+						Some(f) => check_arguments(context, function, f, &HashMap::new(), &f.type_parameters, type_arguments, arguments).map(|(ty, _)| ty),
 						None => error(context, function, format!("undefined function \"{}\"", s)),
-					}
-				},
-				_ => error(context, function, "left hand of a call must be a name"),
+					};
+				}
+			}
+			if !type_arguments.is_empty() {
+				return error(context, function, "only generic functions can be called with type arguments");
 			}
+			let ty = check_expression(context, function)?;
+			check_call(context, function, ty, arguments)
 		},
-		ClassInstantiation { class, arguments } => {
+		ClassInstantiation { class: "Map", type_arguments, arguments } => {
+			if !arguments.is_empty() {
+				return error(context, expression, "invalid number of arguments");
+			}
+			if type_arguments.is_empty() {
+				return error(context, expression, "cannot infer the type arguments of a Map, they have to be given explicitly");
+			}
+			if type_arguments.len() != 2 {
+				return error(context, expression, format!("Map expects 2 type arguments but found {}", type_arguments.len()));
+			}
+			let location = expression_location(context, expression);
+			for type_argument in type_arguments {
+				check_type(context, type_argument, location)?;
+			}
+			Ok(Type::Map(Box::new(type_arguments[0].clone()), Box::new(type_arguments[1].clone())))
+		},
+		ClassInstantiation { class, type_arguments, arguments } => {
+			if context.program.get_interface(class).is_some() {
+				return error(context, expression, format!("cannot instantiate interface \"{}\"", class));
+			}
 			match context.program.get_class(class) {
 				Some(c) => {
-					if let Some(f) = c.get_method("constructor") {
-						check_arguments(context, expression, f, arguments)?;
-					} else {
-						if arguments.len() != 0 {
-							return error(context, expression, "invalid number of arguments");
-						}
+					if c.is_abstract {
+						return error(context, expression, format!("cannot instantiate abstract class \"{}\"", class));
 					}
-					Ok(Type::Class(class))
+					// the constructor is looked up with the type parameters of the class as type arguments so that they can be inferred from the arguments
+					let own_type_arguments: Vec<Type<'a>> = c.type_parameters.iter().map(|type_parameter| Type::TypeParameter(type_parameter.name)).collect();
+					let substitution = match get_method(context.program, class, &own_type_arguments, "constructor") {
+						Some((f, constructor_substitution)) => {
+							check_arguments(context, expression, f, &constructor_substitution, &c.type_parameters, type_arguments, arguments)?.1
+						},
+						None => {
+							if !arguments.is_empty() {
+								return error(context, expression, "invalid number of arguments");
+							}
+							bind_explicit_type_arguments(context, expression, &c.type_parameters, type_arguments)?
+						},
+					};
+					Ok(Type::Class(class, c.type_parameters.iter().map(|type_parameter| substitution[type_parameter.name].clone()).collect()))
 				},
 				None => error(context, expression, format!("undefined class \"{}\"", class)),
 			}
 		},
 		PropertyAccess { object, property } => {
-			match check_expression(context, object)? {
-				Type::Class(class) => {
-					match context.program.get_class(class) {
-						Some(c) => {
-							match c.get_field(property) {
-								Some(ty) => Ok(ty),
-								None => error(context, expression, format!("class \"{}\" does not have a field \"{}\"", class, property)),
-							}
+			let ty = check_expression(context, object)?;
+			match resolve_bound(context, ty) {
+				Type::Class(class, type_arguments) => {
+					if context.program.get_class(class).is_none() && context.program.get_interface(class).is_none() {
+						return error(context, expression, format!("undefined class \"{}\"", class));
+					}
+					if let Some((field, substitution)) = get_field(context.program, class, &type_arguments, property) {
+						return Ok(substitute(&field.ty, &substitution));
+					}
+					// methods can be used as values
+					match get_method(context.program, class, &type_arguments, property) {
+						Some((f, substitution)) if f.name != "constructor" => {
+							context.annotations.insert(expression, Annotation::MethodReference);
+							Ok(function_type(f, &substitution))
 						},
-						None => error(context, expression, format!("undefined class \"{}\"", class)),
+						_ => error(context, expression, format!("{} does not have a field \"{}\"", describe_class(context.program, class), property)),
 					}
 				},
+				Type::Array(_) if *property == "length" => Ok(Type::Number),
+				Type::Array(_) => error(context, expression, format!("arrays do not have a field \"{}\"", property)),
+				Type::Map(_, _) if *property == "size" => Ok(Type::Number),
+				Type::Map(_, _) => error(context, expression, format!("maps do not have a field \"{}\"", property)),
+				Type::Nullable(_) => error(context, expression, "trying to access a property on a value that might be null"),
 				_ => error(context, expression, "trying to access a property on an expression that is not a class"),
 			}
 		},
 		MethodCall { object, method, arguments } => {
-			match check_expression(context, object)? {
-				Type::Class(class) => {
-					match context.program.get_class(class) {
-						Some(c) => {
-							match c.get_method(method) {
-								Some(f) => {
-									check_arguments(context, expression, f, arguments)?;
-									Ok(f.return_type.clone())
-								},
-								None => error(context, expression, format!("class \"{}\" does not have a method \"{}\"", class, method)),
-							}
-						},
-						None => error(context, expression, format!("undefined class \"{}\"", class)),
+			let ty = check_expression(context, object)?;
+			match resolve_bound(context, ty) {
+				Type::Class(class, type_arguments) => {
+					if context.program.get_class(class).is_none() && context.program.get_interface(class).is_none() {
+						return error(context, expression, format!("undefined class \"{}\"", class));
+					}
+					if let Some((f, substitution)) = get_method(context.program, class, &type_arguments, method) {
+						return check_arguments(context, expression, f, &substitution, &[], &[], arguments).map(|(ty, _)| ty);
+					}
+					// fields of a function type can be called like methods
+					match get_field(context.program, class, &type_arguments, method) {
+						Some((field, substitution)) => check_call(context, expression, substitute(&field.ty, &substitution), arguments),
+						None => error(context, expression, format!("{} does not have a method \"{}\"", describe_class(context.program, class), method)),
 					}
 				},
+				Type::Array(element_ty) => check_array_method(context, expression, *element_ty, method, arguments),
+				Type::Map(key_ty, value_ty) => check_map_method(context, expression, *key_ty, *value_ty, method, arguments),
+				Type::Nullable(_) => error(context, expression, "trying to access a property on a value that might be null"),
 				_ => error(context, expression, "trying to access a property on an expression that is not a class"),
 			}
 		},
 		This => {
 			match context.variables.get(&"this") {
 				None => error(context, expression, "this is not available outside of a method"),
-				Some(ty) => Ok(ty.clone()),
+				Some(variable) => Ok(variable.ty.clone()),
+			}
+		},
+		Array(elements) => {
+			let mut element_ty = match elements.first() {
+				Some(element) => check_expression(context, element)?,
+				None => return error(context, expression, "cannot infer the type of an empty array"),
+			};
+			for element in &elements[1..] {
+				let ty = check_expression(context, element)?;
+				element_ty = match common_type(context, &element_ty, &ty) {
+					Some(ty) => ty,
+					None => return error(context, element, format!("array elements must have the same type but found a {:?} and a {:?}", element_ty, ty)),
+				};
+			}
+			if element_ty == Type::Null {
+				return error(context, expression, "cannot infer the type of an array that only contains null");
+			}
+			Ok(Type::Array(Box::new(element_ty)))
+		},
+		Index { array, index } => {
+			let ty = check_expression(context, array)?;
+			match resolve_bound(context, ty) {
+				Type::Array(element_ty) => {
+					assert_type(context, index, Type::Number)?;
+					Ok(*element_ty)
+				},
+				Type::Nullable(_) => error(context, expression, "trying to index a value that might be null"),
+				_ => error(context, expression, "trying to index an expression that is not an array"),
+			}
+		},
+		Lambda { arguments, return_type, body } => {
+			let location = expression_location(context, expression);
+			for (_, ty) in arguments {
+				check_type(context, ty, location)?;
+			}
+			if let Some(return_type) = return_type {
+				check_type(context, return_type, location)?;
+			}
+			// captured variables might be assigned null before the lambda is called, unless they are constant
+			let captured: Vec<(&'a str, bool)> = context.narrowings.iter().filter_map(|(name, _)| context.variables.get(name).filter(|variable| !variable.constant).map(|variable| (*name, variable.non_null))).collect();
+			for (name, _) in &captured {
+				if let Some(variable) = context.variables.get_mut(name) {
+					variable.non_null = false;
+				}
+			}
+			// the body of a lambda is checked like a function body
+			let outer_return_type = std::mem::replace(&mut context.return_type, Type::Void);
+			let outer_inferred_return_type = context.inferred_return_type.take();
+			let outer_loops = std::mem::take(&mut context.loops);
+			let outer_in_constructor = std::mem::replace(&mut context.in_constructor, false);
+			let narrowings = context.narrowings.len();
+			context.variables.push_scope();
+			for (name, ty) in arguments {
+				context.variables.insert(name, Variable::new(ty.clone()));
+			}
+			let return_type = match (return_type, &**body) {
+				(Some(return_type), _) => {
+					context.return_type = return_type.clone();
+					check_statement(context, body)?;
+					return_type.clone()
+				},
+				// the return type of a lambda whose body is an expression is inferred
+				(None, crate::ast::Statement::Return { expression: Some(expression), .. }) => {
+					let ty = check_expression(context, expression)?;
+					if ty == Type::Null {
+						return error(context, expression, "cannot infer the return type of a lambda that returns null");
+					}
+					ty
+				},
+				// and so is the return type of a lambda whose body is a block, from its return statements
+				(None, _) => {
+					context.inferred_return_type = Some(None);
+					check_statement(context, body)?;
+					match context.inferred_return_type.take().flatten() {
+						Some(Type::Null) => return error_at(location, "cannot infer the return type of a lambda that returns null"),
+						Some(ty) => ty,
+						None => Type::Void,
+					}
+				},
+			};
+			context.variables.pop_scope();
+			end_branch(context, narrowings);
+			context.return_type = outer_return_type;
+			context.inferred_return_type = outer_inferred_return_type;
+			context.loops = outer_loops;
+			context.in_constructor = outer_in_constructor;
+			for (name, non_null) in captured.into_iter().rev() {
+				if let Some(variable) = context.variables.get_mut(&name) {
+					variable.non_null = non_null;
+				}
+			}
+			forget_assigned_variables(context, body);
+			let graph = ControlFlowGraph::new(std::slice::from_ref(&**body));
+			check_return_paths(context, &graph, std::slice::from_ref(&**body), &return_type, location, "the lambda")?;
+			Ok(Type::Function(arguments.iter().map(|(_, ty)| ty.clone()).collect(), Box::new(return_type)))
+		},
+		SuperCall { arguments } => {
+			match get_parent_type(context) {
+				Some(Type::Class(parent, type_arguments)) if context.in_constructor => {
+					if let Some((f, substitution)) = get_method(context.program, parent, type_arguments, "constructor") {
+						check_arguments(context, expression, f, &substitution, &[], &[], arguments)?;
+					} else if !arguments.is_empty() {
+						return error(context, expression, "invalid number of arguments");
+					}
+					Ok(Type::Void)
+				},
+				_ => error(context, expression, "super can only be called in the constructor of a derived class"),
+			}
+		},
+		SuperMethodCall { method, arguments } => {
+			match get_parent_type(context) {
+				Some(Type::Class(parent, type_arguments)) => {
+					match get_method(context.program, parent, type_arguments, method) {
+						Some((f, _)) if f.is_abstract => error(context, expression, format!("cannot call abstract method \"{}\" through super", method)),
+						Some((f, substitution)) => {
+							check_arguments(context, expression, f, &substitution, &[], &[], arguments).map(|(ty, _)| ty)
+						},
+						None => error(context, expression, format!("class \"{}\" does not have a method \"{}\"", parent, method)),
+					}
+				},
+				_ => error(context, expression, "super is only available in methods of a derived class"),
+			}
+		},
+	}
+}
+
+// the type of a function or a method that is used as a value
+fn function_type<'a>(f: &crate::ast::Function<'a>, substitution: &Substitution<'a>) -> Type<'a> {
+	let argument_types = f.arguments.iter().map(|(_, ty)| substitute(ty, substitution)).collect();
+	Type::Function(argument_types, Box::new(substitute(&f.return_type, substitution)))
+}
+
+// the type of an expression that evaluates to a value of either of the two types
+fn common_type<'a>(context: &Context<'a>, a: &Type<'a>, b: &Type<'a>) -> Option<Type<'a>> {
+	// if one type is a subclass of the other the result has the type of the parent
+	if is_assignable(context, a, b) {
+		Some(a.clone())
+	} else if is_assignable(context, b, a) {
+		Some(b.clone())
+	} else if *b == Type::Null && *a != Type::Void {
+		Some(Type::Nullable(Box::new(a.clone())))
+	} else if *a == Type::Null && *b != Type::Void {
+		Some(Type::Nullable(Box::new(b.clone())))
+	} else {
+		None
+	}
+}
+
+// checks a call of one of the built-in methods of arrays
+fn check_array_method<'a>(context: &mut Context<'a>, expression: &crate::ast::Expression<'a>, element_ty: Type<'a>, method: &str, arguments: &[Box<crate::ast::Expression<'a>>]) -> Result<Type<'a>, Error> {
+	match method {
+		"push" => {
+			if arguments.len() != 1 {
+				return error(context, expression, "invalid number of arguments");
+			}
+			assert_type(context, &arguments[0], element_ty)?;
+			// like in JS push returns the new length
+			Ok(Type::Number)
+		},
+		"pop" => {
+			if !arguments.is_empty() {
+				return error(context, expression, "invalid number of arguments");
+			}
+			// pop returns null if the array is empty
+			context.annotations.insert(expression, Annotation::UndefinedToNull);
+			Ok(nullable(element_ty))
+		},
+		_ => error(context, expression, format!("arrays do not have a method \"{}\"", method)),
+	}
+}
+
+// checks a call of one of the built-in methods of maps
+fn check_map_method<'a>(context: &mut Context<'a>, expression: &crate::ast::Expression<'a>, key_ty: Type<'a>, value_ty: Type<'a>, method: &str, arguments: &[Box<crate::ast::Expression<'a>>]) -> Result<Type<'a>, Error> {
+	let argument_count = match method {
+		"get" | "has" | "delete" => 1,
+		"set" => 2,
+		_ => return error(context, expression, format!("maps do not have a method \"{}\"", method)),
+	};
+	if arguments.len() != argument_count {
+		return error(context, expression, "invalid number of arguments");
+	}
+	assert_type(context, &arguments[0], key_ty.clone())?;
+	match method {
+		"get" => {
+			// get returns null if the key is missing
+			context.annotations.insert(expression, Annotation::UndefinedToNull);
+			Ok(nullable(value_ty))
+		},
+		"set" => {
+			assert_type(context, &arguments[1], value_ty.clone())?;
+			// like in JS set returns the map itself
+			Ok(Type::Map(Box::new(key_ty), Box::new(value_ty)))
+		},
+		_ => Ok(Type::Boolean),
+	}
+}
+
+// checks a call of a value of a function type and returns the type of its result
+fn check_call<'a>(context: &mut Context<'a>, expression: &crate::ast::Expression, ty: Type<'a>, arguments: &Vec<Box<crate::ast::Expression<'a>>>) -> Result<Type<'a>, Error> {
+	match ty {
+		Type::Function(argument_types, return_type) => {
+			if arguments.len() != argument_types.len() {
+				return error(context, expression, "invalid number of arguments");
 			}
+			for (argument, expected_ty) in arguments.iter().zip(argument_types) {
+				let actual_ty = check_expression_expecting(context, argument, &expected_ty)?;
+				if !is_assignable(context, &expected_ty, &actual_ty) {
+					return error(context, argument, format!("invalid argument type: expected {:?} but found {:?}", expected_ty, actual_ty));
+				}
+			}
+			Ok(*return_type)
 		},
+		Type::Nullable(_) => error(context, expression, "trying to call a value that might be null"),
+		ty => error(context, expression, format!("cannot call a value of type {:?}", ty)),
+	}
+}
+
+// binds the type parameters of a class to the given type arguments
+fn bind_type_arguments<'a>(type_parameters: &[TypeParameter<'a>], type_arguments: &[Type<'a>]) -> Substitution<'a> {
+	type_parameters.iter().map(|type_parameter| type_parameter.name).zip(type_arguments.iter().cloned()).collect()
+}
+
+// the class and its ancestors, each one together with the type arguments it is instantiated with
+fn instantiated_ancestors<'a>(program: &'a crate::ast::Program<'a>, class: &'a crate::ast::Class<'a>, type_arguments: &[Type<'a>]) -> Vec<(&'a crate::ast::Class<'a>, Substitution<'a>)> {
+	let mut ancestors = Vec::new();
+	let mut current = Some((class, bind_type_arguments(&class.type_parameters, type_arguments)));
+	while let Some((class, substitution)) = current {
+		current = match (&class.parent, class.get_parent(program)) {
+			(Some(Type::Class(_, parent_type_arguments)), Some(parent)) => {
+				let parent_type_arguments: Vec<Type<'a>> = parent_type_arguments.iter().map(|ty| substitute(ty, &substitution)).collect();
+				Some((parent, bind_type_arguments(&parent.type_parameters, &parent_type_arguments)))
+			},
+			_ => None,
+		};
+		ancestors.push((class, substitution));
+	}
+	ancestors
+}
+
+// looks up a field of a class or an interface together with the type arguments of the class that defines it
+fn get_field<'a>(program: &'a crate::ast::Program<'a>, class: &str, type_arguments: &[Type<'a>], name: &str) -> Option<(&'a crate::ast::Field<'a>, Substitution<'a>)> {
+	match program.get_class(class) {
+		Some(class) => instantiated_ancestors(program, class, type_arguments).into_iter().find_map(|(class, substitution)| Some((class.get_own_field(name)?, substitution))),
+		None => Some((program.get_interface(class)?.get_field(name)?, HashMap::new())),
+	}
+}
+
+// looks up a method of a class or an interface together with the type arguments of the class that defines it
+fn get_method<'a>(program: &'a crate::ast::Program<'a>, class: &str, type_arguments: &[Type<'a>], name: &str) -> Option<(&'a crate::ast::Function<'a>, Substitution<'a>)> {
+	match program.get_class(class) {
+		Some(class) => instantiated_ancestors(program, class, type_arguments).into_iter().find_map(|(class, substitution)| Some((class.get_own_method(name)?, substitution))),
+		None => Some((program.get_interface(class)?.get_method(name)?, HashMap::new())),
 	}
 }
 
-fn check_arguments<'a>(context: &mut Context<'a>, expression: &crate::ast::Expression, f: &crate::ast::Function, arguments: &Vec<Box<crate::ast::Expression<'a>>>) -> Result<(), Error> {
+fn describe_class(program: &crate::ast::Program, name: &str) -> String {
+	match program.get_interface(name) {
+		Some(_) => format!("interface \"{}\"", name),
+		None => format!("class \"{}\"", name),
+	}
+}
+
+// the parent of the class whose methods are being checked
+fn get_parent_type<'a>(context: &Context<'a>) -> Option<&'a Type<'a>> {
+	match context.variables.get(&"this") {
+		Some(Variable { ty: Type::Class(class, _), .. }) => context.program.get_class(class)?.parent.as_ref(),
+		_ => None,
+	}
+}
+
+// a type parameter with a bound can be used like its bound
+fn resolve_bound<'a>(context: &Context<'a>, ty: Type<'a>) -> Type<'a> {
+	match &ty {
+		Type::TypeParameter(name) => match context.bounds.get(name) {
+			Some(bound) => bound.clone(),
+			None => ty,
+		},
+		_ => ty,
+	}
+}
+
+fn nullable(ty: Type) -> Type {
+	match ty {
+		Type::Nullable(_) | Type::Null => ty,
+		_ => Type::Nullable(Box::new(ty)),
+	}
+}
+
+fn is_nullable(ty: &Type) -> bool {
+	matches!(ty, Type::Nullable(_) | Type::Null)
+}
+
+fn is_nullable_variable(context: &Context, expression: &crate::ast::Expression) -> bool {
+	match expression {
+		crate::ast::Expression::Name(s) => matches!(context.variables.get(s), Some(Variable { ty: Type::Nullable(_), .. })),
+		_ => false,
+	}
+}
+
+fn check_assignment_target<'a>(context: &mut Context<'a>, expression: &crate::ast::Expression<'a>, msg: &str) -> Result<Type<'a>, Error> {
+	use crate::ast::Expression::*;
+	match expression {
+		Name(s) => {
+			if let Some(Variable { constant: true, .. }) = context.variables.get(s) {
+				return error(context, expression, format!("cannot assign to constant \"{}\"", s));
+			}
+			check_expression(context, expression)
+		},
+		PropertyAccess { object, property } => {
+			let ty = check_expression(context, expression)?;
+			let object_ty = check_expression(context, object)?;
+			match resolve_bound(context, object_ty) {
+				Type::Class(class, type_arguments) => {
+					if let Some(Annotation::MethodReference) = context.annotations.get(&(expression as * const crate::ast::Expression)) {
+						return error(context, expression, format!("cannot assign to method \"{}\"", property));
+					}
+					let readonly = get_field(context.program, class, &type_arguments, property).is_some_and(|(field, _)| field.readonly);
+					if readonly && !(context.in_constructor && matches!(**object, This)) {
+						return error(context, expression, format!("cannot assign to readonly field \"{}\" outside of the constructor", property));
+					}
+				},
+				Type::Array(_) => return error(context, expression, "cannot assign to the length of an array"),
+				Type::Map(_, _) => return error(context, expression, "cannot assign to the size of a map"),
+				_ => {},
+			}
+			Ok(ty)
+		},
+		Index { .. } => check_expression(context, expression),
+		_ => error(context, expression, msg),
+	}
+}
+
+// checks the arguments of a call and returns the type of its result together with the type arguments of the called function
+// substitution contains the type arguments of the class the function is a method of
+fn check_arguments<'a>(context: &mut Context<'a>, expression: &crate::ast::Expression, f: &crate::ast::Function<'a>, substitution: &Substitution<'a>, type_parameters: &[TypeParameter<'a>], type_arguments: &[Type<'a>], arguments: &Vec<Box<crate::ast::Expression<'a>>>) -> Result<(Type<'a>, Substitution<'a>), Error> {
 	if arguments.len() != f.arguments.len() {
-		error(context, expression, "invalid number of arguments")
+		return error(context, expression, "invalid number of arguments");
+	}
+	let expected_types: Vec<Type<'a>> = f.arguments.iter().map(|(_, ty)| substitute(ty, substitution)).collect();
+	// type arguments are either given explicitly or inferred from the types of the arguments
+	let infer = type_arguments.is_empty() && !type_parameters.is_empty();
+	let mut type_substitution = if infer {
+		HashMap::new()
 	} else {
-		let argument_types = f.arguments.iter().map(|(_, ty)| ty);
-		for (argument, expected_ty) in arguments.iter().zip(argument_types) {
-			let actual_ty = check_expression(context, argument)?;
-			if &actual_ty != expected_ty {
-				return error(context, argument, format!("invalid argument type: expected {:?} but found {:?}", expected_ty, actual_ty));
+		bind_explicit_type_arguments(context, expression, type_parameters, type_arguments)?
+	};
+	// the arguments are checked in order since they can narrow variables
+	// only an empty array literal has to wait for its expected type during inference, it has no type of its own but also no side effects
+	let mut argument_types = Vec::new();
+	for (expected_ty, argument) in expected_types.iter().zip(arguments) {
+		argument_types.push(match &**argument {
+			crate::ast::Expression::Array(elements) if infer && elements.is_empty() => None,
+			_ if infer => Some(check_expression(context, argument)?),
+			_ => Some(check_expression_expecting(context, argument, &substitute(expected_ty, &type_substitution))?),
+		});
+	}
+	if infer {
+		for (expected_ty, (argument, actual_ty)) in expected_types.iter().zip(arguments.iter().zip(&argument_types)) {
+			if let Some(actual_ty) = actual_ty {
+				infer_type_arguments(context, argument, expected_ty, actual_ty, type_parameters, &mut type_substitution)?;
 			}
 		}
-		Ok(())
+		for type_parameter in type_parameters {
+			if !type_substitution.contains_key(type_parameter.name) {
+				return error(context, expression, format!("cannot infer type parameter \"{}\", it has to be given explicitly", type_parameter.name));
+			}
+		}
+		check_bounds(context, type_parameters, &type_substitution, expression_location(context, expression))?;
+	}
+	for (expected_ty, (argument, actual_ty)) in expected_types.iter().zip(arguments.iter().zip(argument_types)) {
+		let expected_ty = substitute(expected_ty, &type_substitution);
+		let actual_ty = match actual_ty {
+			Some(actual_ty) => actual_ty,
+			None => check_expression_expecting(context, argument, &expected_ty)?,
+		};
+		if !is_assignable(context, &expected_ty, &actual_ty) {
+			return error(context, argument, format!("invalid argument type: expected {:?} but found {:?}", expected_ty, actual_ty));
+		}
 	}
+	let return_type = substitute(&substitute(&f.return_type, substitution), &type_substitution);
+	Ok((return_type, type_substitution))
 }
 
-fn assert_type<'a>(context: &mut Context<'a>, expression: &crate::ast::Expression<'a>, expected_ty: Type) -> Result<(), Error> {
-	let actual_ty = check_expression(context, expression)?;
-	if actual_ty == expected_ty {
-		Ok(())
+fn bind_explicit_type_arguments<'a>(context: &Context<'a>, expression: &crate::ast::Expression, type_parameters: &[TypeParameter<'a>], type_arguments: &[Type<'a>]) -> Result<Substitution<'a>, Error> {
+	let location = expression_location(context, expression);
+	if type_arguments.len() != type_parameters.len() {
+		if type_arguments.is_empty() {
+			return error_at(location, format!("cannot infer type parameter \"{}\", it has to be given explicitly", type_parameters[0].name));
+		}
+		return error_at(location, format!("expected {} type arguments but found {}", type_parameters.len(), type_arguments.len()));
+	}
+	for type_argument in type_arguments {
+		check_type(context, type_argument, location)?;
+	}
+	let substitution = bind_type_arguments(type_parameters, type_arguments);
+	check_bounds(context, type_parameters, &substitution, location)?;
+	Ok(substitution)
+}
+
+// infers type arguments by matching the type of an argument against the type of the corresponding parameter
+fn infer_type_arguments<'a>(context: &Context<'a>, argument: &crate::ast::Expression, expected_ty: &Type<'a>, actual_ty: &Type<'a>, type_parameters: &[TypeParameter<'a>], substitution: &mut Substitution<'a>) -> Result<(), Error> {
+	match (expected_ty, actual_ty) {
+		(Type::TypeParameter(name), _) if type_parameters.iter().any(|type_parameter| type_parameter.name == *name) => {
+			match substitution.get(name) {
+				None => {
+					substitution.insert(name, actual_ty.clone());
+				},
+				// if the type parameter is used more than once the more general type wins
+				Some(inferred_ty) => {
+					if is_assignable(context, actual_ty, inferred_ty) {
+						substitution.insert(name, actual_ty.clone());
+					} else if !is_assignable(context, inferred_ty, actual_ty) {
+						return error(context, argument, format!("cannot infer type parameter \"{}\": found a {:?} and a {:?}", name, inferred_ty, actual_ty));
+					}
+				},
+			}
+		},
+		(Type::Class(expected, expected_arguments), Type::Class(actual, actual_arguments)) if expected == actual => {
+			for (expected_ty, actual_ty) in expected_arguments.iter().zip(actual_arguments) {
+				infer_type_arguments(context, argument, expected_ty, actual_ty, type_parameters, substitution)?;
+			}
+		},
+		(Type::Array(expected_ty), Type::Array(actual_ty)) => {
+			infer_type_arguments(context, argument, expected_ty, actual_ty, type_parameters, substitution)?;
+		},
+		(Type::Map(expected_key_ty, expected_value_ty), Type::Map(actual_key_ty, actual_value_ty)) => {
+			infer_type_arguments(context, argument, expected_key_ty, actual_key_ty, type_parameters, substitution)?;
+			infer_type_arguments(context, argument, expected_value_ty, actual_value_ty, type_parameters, substitution)?;
+		},
+		// null does not tell anything about the type parameter
+		(Type::Nullable(_), Type::Null) => {},
+		(Type::Nullable(expected_ty), Type::Nullable(actual_ty)) => {
+			infer_type_arguments(context, argument, expected_ty, actual_ty, type_parameters, substitution)?;
+		},
+		(Type::Nullable(expected_ty), _) => {
+			infer_type_arguments(context, argument, expected_ty, actual_ty, type_parameters, substitution)?;
+		},
+		(Type::Function(expected_arguments, expected_return_type), Type::Function(actual_arguments, actual_return_type)) if expected_arguments.len() == actual_arguments.len() => {
+			for (expected_ty, actual_ty) in expected_arguments.iter().zip(actual_arguments) {
+				infer_type_arguments(context, argument, expected_ty, actual_ty, type_parameters, substitution)?;
+			}
+			infer_type_arguments(context, argument, expected_return_type, actual_return_type, type_parameters, substitution)?;
+		},
+		_ => {},
+	}
+	Ok(())
+}
+
+// replaces type parameters with their type arguments
+fn substitute<'a>(ty: &Type<'a>, substitution: &Substitution<'a>) -> Type<'a> {
+	match ty {
+		Type::TypeParameter(name) => substitution.get(name).cloned().unwrap_or_else(|| ty.clone()),
+		Type::Class(name, type_arguments) => Type::Class(name, type_arguments.iter().map(|ty| substitute(ty, substitution)).collect()),
+		Type::Nullable(ty) => Type::Nullable(Box::new(substitute(ty, substitution))),
+		Type::Array(ty) => Type::Array(Box::new(substitute(ty, substitution))),
+		Type::Map(key_ty, value_ty) => Type::Map(Box::new(substitute(key_ty, substitution)), Box::new(substitute(value_ty, substitution))),
+		Type::Function(argument_types, return_type) => Type::Function(argument_types.iter().map(|ty| substitute(ty, substitution)).collect(), Box::new(substitute(return_type, substitution))),
+		_ => ty.clone(),
+	}
+}
+
+// checks that an expression has the expected type and returns its actual type
+fn assert_type<'a>(context: &mut Context<'a>, expression: &crate::ast::Expression<'a>, expected_ty: Type<'a>) -> Result<Type<'a>, Error> {
+	let actual_ty = check_expression_expecting(context, expression, &expected_ty)?;
+	if is_assignable(context, &expected_ty, &actual_ty) {
+		Ok(actual_ty)
 	} else {
 		let msg = format!("type mismatch: expected a {:?} but found a {:?}", expected_ty, actual_ty);
 		error(context, expression, msg)
 	}
 }
 
+// like check_expression but array literals and new Map() take their types from the expected type so that [] or [1] can be used as a number?[]
+fn check_expression_expecting<'a>(context: &mut Context<'a>, expression: &crate::ast::Expression<'a>, expected_ty: &Type<'a>) -> Result<Type<'a>, Error> {
+	let expected_ty = match expected_ty {
+		Type::Nullable(ty) => &**ty,
+		ty => ty,
+	};
+	match (expression, expected_ty) {
+		(crate::ast::Expression::Array(elements), Type::Array(element_ty)) => {
+			for element in elements {
+				assert_type(context, element, (**element_ty).clone())?;
+			}
+			Ok(expected_ty.clone())
+		},
+		(crate::ast::Expression::ClassInstantiation { class: "Map", type_arguments, arguments }, Type::Map(_, _)) if type_arguments.is_empty() && arguments.is_empty() => {
+			Ok(expected_ty.clone())
+		},
+		_ => check_expression(context, expression),
+	}
+}
+
+fn is_assignable<'a>(context: &Context<'a>, expected_ty: &Type<'a>, actual_ty: &Type<'a>) -> bool {
+	is_subtype(context, expected_ty, actual_ty, &mut Vec::new())
+}
+
+// an instance of a class can be used wherever one of its ancestors or an interface it conforms to is expected
+// a nullable type accepts null and everything its underlying type accepts
+// assumptions contains the pairs of types and interfaces whose conformance is currently being checked so that recursive interfaces terminate
+fn is_subtype<'a>(context: &Context<'a>, expected_ty: &Type<'a>, actual_ty: &Type<'a>, assumptions: &mut Vec<(Type<'a>, &'a str)>) -> bool {
+	match (expected_ty, actual_ty) {
+		(Type::Class(expected, expected_arguments), Type::Class(actual, actual_arguments)) => {
+			if expected_ty == actual_ty {
+				return true;
+			}
+			// the type arguments of a generic ancestor have to match exactly
+			if let Some(class) = context.program.get_class(actual) {
+				for (ancestor, substitution) in instantiated_ancestors(context.program, class, actual_arguments) {
+					if ancestor.name == *expected {
+						return ancestor.type_parameters.iter().map(|type_parameter| substitute(&Type::TypeParameter(type_parameter.name), &substitution)).eq(expected_arguments.iter().cloned());
+					}
+				}
+			}
+			match context.program.get_interface(expected) {
+				Some(interface) => {
+					if assumptions.contains(&(actual_ty.clone(), expected)) {
+						return true;
+					}
+					assumptions.push((actual_ty.clone(), expected));
+					let result = check_conformance(context, actual_ty, interface, assumptions).is_ok();
+					assumptions.pop();
+					result
+				},
+				None => false,
+			}
+		},
+		(Type::Nullable(_), Type::Null) => true,
+		(Type::Nullable(expected_ty), Type::Nullable(actual_ty)) => is_subtype(context, expected_ty, actual_ty, assumptions),
+		(Type::Nullable(expected_ty), _) => is_subtype(context, expected_ty, actual_ty, assumptions),
+		// functions accept the arguments of the expected type and return something the expected type could return
+		(Type::Function(expected_arguments, expected_return_type), Type::Function(actual_arguments, actual_return_type)) => {
+			expected_arguments.len() == actual_arguments.len()
+				&& expected_arguments.iter().zip(actual_arguments).all(|(expected_ty, actual_ty)| is_subtype(context, actual_ty, expected_ty, assumptions))
+				&& (**expected_return_type == Type::Void || is_subtype(context, expected_return_type, actual_return_type, assumptions))
+		},
+		// arrays and maps can be modified so their element types have to match exactly
+		(Type::Array(_), Type::Array(_)) | (Type::Map(_, _), Type::Map(_, _)) => expected_ty == actual_ty,
+		// a type parameter can be used wherever its bound is expected
+		(_, Type::TypeParameter(name)) if expected_ty != actual_ty && context.bounds.contains_key(name) => {
+			is_subtype(context, expected_ty, &context.bounds[name], assumptions)
+		},
+		_ => expected_ty == actual_ty,
+	}
+}
+
 fn error<T, S: Into<String>>(context: &Context, expression: &crate::ast::Expression, msg: S) -> Result<T, Error> {
+	error_at(expression_location(context, expression), msg)
+}
+
+fn expression_location(context: &Context, expression: &crate::ast::Expression) -> Location {
 	let key: * const crate::ast::Expression = expression;
-	let i = context.program.locations.get(&key).copied().unwrap_or_default();
+	context.program.locations.get(&key).copied().unwrap_or_default()
+}
+
+fn error_at<T, S: Into<String>>(i: Location, msg: S) -> Result<T, Error> {
 	Err(Error {
 		i,
 		msg: msg.into(),
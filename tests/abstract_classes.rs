@@ -0,0 +1,35 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+#[test]
+fn instantiation() {
+	assert_error("abstract class S {\n}\nfunction f() {\n\tlet s = new S();\n}\n", "cannot instantiate abstract class \"S\"");
+	// an abstract class can still be used as a type
+	assert_compiles("abstract class S {\n\tabstract area(): number;\n}\nclass C extends S {\n\tarea(): number {\n\t\treturn 1;\n\t}\n}\nfunction f(): number {\n\tlet s: S = new C();\n\treturn s.area();\n}\n");
+}
+
+#[test]
+fn abstract_methods() {
+	assert_error("abstract class S {\n\tabstract area(): number;\n}\nclass C extends S {\n}\n", "class \"C\" does not implement abstract method \"area\" of class \"S\"");
+	assert_error("abstract class S {\n\tabstract area(): number;\n}\nclass C extends S {\n\tarea(): string {\n\t\treturn \"a\";\n\t}\n}\n", "method \"area\" must return a Number like the method it overrides");
+	assert_error("class C {\n\tabstract area(): number;\n}\n", "abstract method \"area\" can only be declared in an abstract class");
+}
+
+#[test]
+fn concrete_methods() {
+	// an abstract class can have methods with a body that use the abstract ones
+	assert_compiles("abstract class S {\n\tabstract area(): number;\n\tdouble(): number {\n\t\treturn this.area() * 2;\n\t}\n}\n");
+}
+
+#[test]
+fn intermediate_classes() {
+	// an abstract class in between does not have to implement it but the concrete class at the end does
+	assert_error("abstract class S {\n\tabstract area(): number;\n}\nabstract class T extends S {\n}\nclass C extends T {\n}\n", "class \"C\" does not implement abstract method \"area\" of class \"S\"");
+	assert_compiles("abstract class S {\n\tabstract area(): number;\n}\nabstract class T extends S {\n}\nclass C extends T {\n\tarea(): number {\n\t\treturn 2;\n\t}\n}\n");
+}
+
+#[test]
+fn super_calls() {
+	assert_error("abstract class S {\n\tabstract area(): number;\n}\nclass C extends S {\n\tarea(): number {\n\t\treturn super.area();\n\t}\n}\n", "cannot call abstract method \"area\" through super");
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+const CLASS: &str = "class A {\n\tv: number = 1;\n}\n";
+const ERROR: &str = "trying to access a property on a value that might be null";
+
+#[test]
+fn literal_arguments() {
+	// an array literal takes its element type from the parameter
+	assert_compiles("function g(a: number?[]) {\n}\nfunction f() {\n\tg([1, null]);\n\tg([]);\n}\n");
+	assert_compiles("function g<T>(a: T?[], b: T) {\n}\nfunction f() {\n\tg<number>([1, null], 1);\n}\n");
+	assert_compiles("function g<T>(a: T[], b: T) {\n}\nfunction f() {\n\tg([], 1);\n}\n");
+	assert_error("function g(a: number[]) {\n}\nfunction f() {\n\tg([\"a\"]);\n}\n", "type mismatch: expected a Number but found a String");
+}
+
+#[test]
+fn argument_order() {
+	// arguments are evaluated from left to right so an assignment in a later argument cannot narrow an earlier one
+	assert_error(&format!("{}function g(a: number[], b: A?) {{\n}}\nfunction f(x: A?) {{\n\tg([x.v], x = new A());\n}}\n", CLASS), ERROR);
+	assert_error(&format!("{}function g<T>(a: T[], b: A?) {{\n}}\nfunction f(x: A?) {{\n\tg([x.v], x = new A());\n}}\n", CLASS), ERROR);
+	assert_error(&format!("{}class B {{\n\tm(a: number[], b: A?) {{\n\t}}\n}}\nfunction f(x: A?, o: B) {{\n\to.m([x.v], x = new A());\n}}\n", CLASS), ERROR);
+	assert_error(&format!("{}class B {{\n\tconstructor(a: number[], b: A?) {{\n\t}}\n}}\nfunction f(x: A?) {{\n\tlet b = new B([x.v], x = new A());\n}}\n", CLASS), ERROR);
+	// and an assignment of null in a later argument does not affect an earlier one
+	assert_compiles(&format!("{}function g(a: number[], b: A?) {{\n}}\nfunction f(x: A?) {{\n\tif (x != null) {{\n\t\tg([x.v], x = null);\n\t}}\n}}\n", CLASS));
+	// but an assignment in an earlier argument affects the later ones
+	assert_compiles(&format!("{}function g(b: A?, a: number[]) {{\n}}\nfunction f(x: A?) {{\n\tg(x = new A(), [x.v]);\n}}\n", CLASS));
+	assert_error(&format!("{}function g(b: A?, a: number[]) {{\n}}\nfunction f(x: A?) {{\n\tif (x != null) {{\n\t\tg(x = null, [x.v]);\n\t}}\n}}\n", CLASS), ERROR);
+}
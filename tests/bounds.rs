@@ -0,0 +1,41 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+const TYPES: &str = "interface Named {\n\treadonly name: string;\n}\nclass Dog {\n\tname: string = \"rex\";\n}\nclass Rock {\n\tweight: number = 1;\n}\n";
+const GREET: &str = "function greet<T extends Named>(x: T): string {\n\treturn x.name;\n}\n";
+const BOX: &str = "class Box<T extends Named> {\n\tv: T;\n\tconstructor(v: T) {\n\t\tthis.v = v;\n\t}\n\tname(): string {\n\t\treturn this.v.name;\n\t}\n}\n";
+
+const ROCK_ERROR: &str = "type argument Class(\"Rock\", []) does not satisfy the bound Class(\"Named\", []) of type parameter \"T\"";
+
+// a program with the types above and a function f with the given body
+fn program(declarations: &str, body: &str) -> String {
+	format!("{}{}function f() {{\n\t{}\n}}\n", TYPES, declarations, body)
+}
+
+#[test]
+fn explicit_instantiation() {
+	assert_compiles(&program(GREET, "greet<Dog>(new Dog());"));
+	assert_error(&program(GREET, "greet<Rock>(new Rock());"), ROCK_ERROR);
+	assert_error(&program(GREET, "greet<number>(1);"), "type argument Number does not satisfy the bound Class(\"Named\", []) of type parameter \"T\"");
+}
+
+#[test]
+fn inferred_instantiation() {
+	assert_compiles(&program(GREET, "greet(new Dog());"));
+	assert_error(&program(GREET, "greet(new Rock());"), ROCK_ERROR);
+	assert_error(&program(BOX, "let b = new Box(new Rock());"), ROCK_ERROR);
+	// a type parameter only satisfies a bound if its own bound does
+	assert_error(&format!("{}{}function g<U>(x: U): string {{\n\treturn greet(x);\n}}\n", TYPES, GREET), "type argument TypeParameter(\"U\") does not satisfy the bound Class(\"Named\", []) of type parameter \"T\"");
+	assert_compiles(&format!("{}{}function g<U extends Named>(x: U): string {{\n\treturn greet(x);\n}}\n", TYPES, GREET));
+}
+
+#[test]
+fn class_instantiation() {
+	assert_compiles(&program(BOX, "let b = new Box<Dog>(new Dog());"));
+	assert_error(&program(BOX, "let b = new Box<Rock>(new Rock());"), ROCK_ERROR);
+	// bounds are also checked in type annotations
+	assert_error(&format!("{}{}function f(b: Box<Rock>) {{\n}}\n", TYPES, BOX), ROCK_ERROR);
+	// without the bound the members of T cannot be used
+	assert_error("class Box<T> {\n\tv: T;\n\tconstructor(v: T) {\n\t\tthis.v = v;\n\t}\n\tname(): string {\n\t\treturn this.v.name;\n\t}\n}\n", "trying to access a property on an expression that is not a class");
+}
@@ -0,0 +1,63 @@
+// every test file is compiled as its own crate and none of them uses all of the helpers
+#![allow(dead_code)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+// runs the compiler on a program and returns what it printed to stdout and stderr
+pub fn run(source: &str) -> (String, String) {
+	let path = std::env::temp_dir().join(format!("superscript-{}-{}.ss", std::process::id(), COUNTER.fetch_add(1, Ordering::SeqCst)));
+	std::fs::write(&path, source).unwrap();
+	let output = std::process::Command::new(env!("CARGO_BIN_EXE_superscript")).arg(&path).output().unwrap();
+	std::fs::remove_file(&path).unwrap();
+	(String::from_utf8(output.stdout).unwrap(), String::from_utf8(output.stderr).unwrap())
+}
+
+// compiles a program and returns the generated JS or the error output
+pub fn compile(source: &str) -> Result<String, String> {
+	let (stdout, stderr) = run(source);
+	let mut lines = stdout.lines();
+	match lines.next() {
+		Some(line) if line.contains("type check successful") => Ok(lines.collect::<Vec<_>>().join("\n")),
+		_ => Err(stderr),
+	}
+}
+
+pub fn assert_compiles(source: &str) -> String {
+	match compile(source) {
+		Ok(js) => js,
+		Err(e) => panic!("failed to compile {}: {}", source, e),
+	}
+}
+
+pub fn assert_error(source: &str, expected: &str) {
+	match compile(source) {
+		Ok(js) => panic!("{} compiled to {} but an error was expected", source, js),
+		Err(e) => assert!(e.contains(expected), "unexpected error while compiling {}: {}", source, e),
+	}
+}
+
+// the program has to compile but with the expected warning
+pub fn assert_warning(source: &str, expected: &str) {
+	let (stdout, stderr) = run(source);
+	assert!(stdout.contains("type check successful"), "failed to compile {}: {}", source, stderr);
+	assert!(stderr.contains(expected), "expected a warning \"{}\" while compiling {} but found: {}", expected, source, stderr);
+}
+
+pub fn assert_no_warnings(source: &str) {
+	let (stdout, stderr) = run(source);
+	assert!(stdout.contains("type check successful"), "failed to compile {}: {}", source, stderr);
+	assert!(stderr.is_empty(), "unexpected output while compiling {}: {}", source, stderr);
+}
+
+// compiles a program, runs the JS with node and returns what the given JS expression evaluates to at the end of it
+pub fn evaluate(source: &str, expression: &str) -> String {
+	let js = assert_compiles(source);
+	let path = std::env::temp_dir().join(format!("superscript-{}-{}.js", std::process::id(), COUNTER.fetch_add(1, Ordering::SeqCst)));
+	std::fs::write(&path, format!("{}\nconsole.log({});\n", js, expression)).unwrap();
+	let output = std::process::Command::new("node").arg(&path).output().unwrap();
+	std::fs::remove_file(&path).unwrap();
+	assert!(output.status.success(), "failed to run {}: {}", js, String::from_utf8_lossy(&output.stderr));
+	String::from_utf8(output.stdout).unwrap().trim_end().to_string()
+}
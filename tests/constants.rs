@@ -0,0 +1,39 @@
+mod common;
+
+use common::{assert_compiles, assert_error, evaluate};
+
+#[test]
+fn const_declarations() {
+	let source = "function f(): number {\n\tconst x = 2;\n\tconst y: number = x + 1;\n\treturn x * y;\n}\n";
+	let js = assert_compiles(source);
+	assert!(js.contains("\tconst x = 2;\n"), "{}", js);
+	assert!(js.contains("\tconst y = (x + 1);\n"), "{}", js);
+	assert_eq!(evaluate(source, "f()"), "6");
+	assert_error("function f() {\n\tconst x: number;\n}\n", "expected an initializer");
+	assert_error("function f() {\n\tconst x: number = \"a\";\n}\n", "type mismatch: expected a Number but found a String");
+}
+
+#[test]
+fn assignments_to_constants() {
+	assert_error("function f() {\n\tconst x = 1;\n\tx = 2;\n}\n", "cannot assign to constant \"x\"");
+	assert_error("function f() {\n\tconst x = 1;\n\tx += 2;\n}\n", "cannot assign to constant \"x\"");
+	assert_error("function f() {\n\tconst x = 1;\n\tx++;\n}\n", "cannot assign to constant \"x\"");
+	assert_error("function f() {\n\tconst x = 1;\n\t--x;\n}\n", "cannot assign to constant \"x\"");
+	// the loop variable of a for of loop can be constant too
+	let js = assert_compiles("function f(a: number[]) {\n\tfor (const x of a) {\n\t}\n}\n");
+	assert!(js.contains("for (const x of a)"), "{}", js);
+	assert_error("function f(a: number[]) {\n\tfor (const x of a) {\n\t\tx = 1;\n\t}\n}\n", "cannot assign to constant \"x\"");
+	// a constant can be shadowed by a mutable variable in an inner scope
+	assert_compiles("function f() {\n\tconst x = 1;\n\t{\n\t\tlet x = 2;\n\t\tx = 3;\n\t}\n}\n");
+}
+
+#[test]
+fn readonly_fields() {
+	let class = "class A {\n\treadonly x: number;\n\tconstructor() {\n\t\tthis.x = 1;\n\t\tthis.x += 1;\n\t}\n}\n";
+	assert_eq!(evaluate(class, "new A().x"), "2");
+	assert_error(&format!("{}function f(a: A) {{\n\ta.x = 2;\n}}\n", class), "cannot assign to readonly field \"x\" outside of the constructor");
+	assert_error(&format!("{}function f(a: A) {{\n\ta.x++;\n}}\n", class), "cannot assign to readonly field \"x\" outside of the constructor");
+	assert_error("class A {\n\treadonly x: number = 1;\n\tm() {\n\t\tthis.x = 2;\n\t}\n}\n", "cannot assign to readonly field \"x\" outside of the constructor");
+	// only the fields of this can be assigned in the constructor
+	assert_error("class A {\n\treadonly x: number = 1;\n\tconstructor(a: A) {\n\t\ta.x = 2;\n\t}\n}\n", "cannot assign to readonly field \"x\" outside of the constructor");
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::{assert_compiles, assert_error, assert_warning, assert_no_warnings};
+
+#[test]
+fn missing_return() {
+	assert_compiles("function f(x: boolean): number {\n\tif (x) {\n\t\treturn 1;\n\t} else {\n\t\treturn 2;\n\t}\n}\n");
+	assert_error("function f(x: boolean): number {\n\tif (x) {\n\t\treturn 1;\n\t} else {\n\t}\n}\n", "not all paths of \"f\" return a value");
+	assert_error("function f(x: boolean): number {\n\tif (x)\n\t\treturn 1;\n}\n", "not all paths of \"f\" return a value");
+	assert_error("function f(): number {\n}\n", "not all paths of \"f\" return a value");
+	assert_compiles("function f(x: number): number {\n\tdo {\n\t\treturn 1;\n\t} while (x > 0);\n}\n");
+	// functions without a return type do not need a return statement
+	assert_no_warnings("function f(x: boolean) {\n\tif (x) {\n\t\treturn;\n\t}\n}\n");
+}
+
+#[test]
+fn infinite_loops() {
+	// the end of a function after an infinite loop can only be reached through a break
+	assert_compiles("function f(): number {\n\twhile (true) {\n\t}\n}\n");
+	assert_compiles("function f(): number {\n\tfor (;;) {\n\t}\n}\n");
+	assert_compiles("function f(x: number): number {\n\tfor (;;) {\n\t\tif (x > 0) {\n\t\t\treturn x;\n\t\t}\n\t}\n}\n");
+	assert_error("function f(): number {\n\twhile (true) {\n\t\tbreak;\n\t}\n}\n", "not all paths of \"f\" return a value");
+	assert_error("function f(): number {\n\tfor (;;) {\n\t\tbreak;\n\t}\n}\n", "not all paths of \"f\" return a value");
+	assert_error("function f(x: boolean): number {\n\twhile (x) {\n\t\treturn 1;\n\t}\n}\n", "not all paths of \"f\" return a value");
+}
+
+#[test]
+fn unreachable_code() {
+	assert_warning("function f(): number {\n\treturn 1;\n\tlet x = 2;\n}\n", "unreachable code");
+	assert_warning("function f() {\n\twhile (true) {\n\t\tbreak;\n\t\tf();\n\t}\n}\n", "unreachable code");
+	assert_warning("function f() {\n\tfor (;;) {\n\t\tcontinue;\n\t\tlet y = 1;\n\t}\n}\n", "unreachable code");
+	assert_warning("function f(x: boolean) {\n\tif (x) {\n\t\treturn;\n\t} else {\n\t\treturn;\n\t}\n\tf(x);\n}\n", "unreachable code");
+	assert_warning("function f(): number {\n\twhile (true) {\n\t}\n\treturn 1;\n}\n", "unreachable code");
+	assert_no_warnings("function f(x: boolean): number {\n\tif (x) {\n\t\treturn 1;\n\t}\n\treturn 2;\n}\n");
+	assert_no_warnings("function f() {\n\twhile (true) {\n\t\tbreak;\n\t}\n\tf();\n}\n");
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+// a function with a boolean argument c that can be used for conditions
+fn function(body: &str) -> String {
+	format!("function f(c: boolean) {{\n\t{}\n}}\n", body)
+}
+
+const ERROR: &str = "variable \"x\" is used before being assigned";
+
+#[test]
+fn straight_line() {
+	assert_error(&function("let x: number;\n\tlet y = x;"), ERROR);
+	assert_compiles(&function("let x: number;\n\tx = 1;\n\tlet y = x;"));
+	// the right hand side is evaluated before the assignment
+	assert_error(&function("let x: number;\n\tx = x;"), ERROR);
+}
+
+#[test]
+fn branches() {
+	assert_error(&function("let x: number;\n\tif (c) {\n\t\tx = 1;\n\t}\n\tlet y = x;"), ERROR);
+	assert_compiles(&function("let x: number;\n\tif (c) {\n\t\tx = 1;\n\t} else {\n\t\tx = 2;\n\t}\n\tlet y = x;"));
+	// a branch that returns does not reach the use
+	assert_compiles(&function("let x: number;\n\tif (c) {\n\t\treturn;\n\t} else {\n\t\tx = 1;\n\t}\n\tlet y = x;"));
+	assert_compiles(&function("let x: number;\n\tlet y = c ? (x = 1) : (x = 2);\n\tlet z = x;"));
+	// the right operand of && is not always evaluated
+	assert_error(&function("let x: number;\n\tlet b = c && (x = 1) == 1;\n\tlet y = x;"), ERROR);
+}
+
+#[test]
+fn loops() {
+	// the body of a loop might not run at all
+	assert_error(&function("let x: number;\n\twhile (c) {\n\t\tx = 1;\n\t}\n\tlet y = x;"), ERROR);
+	assert_error(&function("let x: number;\n\tfor (let i = 0; i < 3; i++) {\n\t\tx = i;\n\t}\n\tlet y = x;"), ERROR);
+	assert_error(&function("let x: number;\n\tfor (let i of 0..3) {\n\t\tx = i;\n\t}\n\tlet y = x;"), ERROR);
+	// but the body of a do while loop always runs
+	assert_compiles(&function("let x: number;\n\tdo {\n\t\tx = 1;\n\t} while (c);\n\tlet y = x;"));
+	// and an infinite loop can only be left through the break
+	assert_compiles(&function("let x: number;\n\twhile (true) {\n\t\tx = 1;\n\t\tbreak;\n\t}\n\tlet y = x;"));
+	// a use in the loop body can be reached before the assignment in a later iteration
+	assert_error(&function("let x: number;\n\twhile (c) {\n\t\tlet y = x;\n\t\tx = 1;\n\t}"), ERROR);
+}
+
+#[test]
+fn compound_assignment() {
+	assert_error(&function("let x: number;\n\tx += 1;"), ERROR);
+	assert_error(&function("let x: number;\n\tx++;"), ERROR);
+	assert_error(&function("let x: number;\n\t--x;"), ERROR);
+	assert_compiles(&function("let x: number;\n\tx = 1;\n\tx += 1;\n\tx++;"));
+}
+
+#[test]
+fn lambdas() {
+	// a lambda could be called right away so it cannot capture a variable that is not assigned yet
+	assert_error(&function("let x: number;\n\tlet g = () => x;"), ERROR);
+	assert_compiles(&function("let x: number;\n\tx = 1;\n\tlet g = () => x;"));
+	// and an assignment inside of a lambda does not count because the lambda might never be called
+	assert_error(&function("let x: number;\n\tlet g = () => {\n\t\tx = 1;\n\t};\n\tlet y = x;"), ERROR);
+}
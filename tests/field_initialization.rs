@@ -0,0 +1,58 @@
+mod common;
+
+use common::{assert_compiles, assert_error, evaluate};
+
+const BASE: &str = "class B {\n\tconstructor(n: number) {\n\t}\n}\n";
+
+#[test]
+fn unassigned_fields() {
+	assert_error("class A {\n\tx: number;\n\tconstructor() {\n\t}\n}\n", "field \"x\" is not assigned before the constructor returns");
+	// a field that is only assigned on some paths
+	assert_error("class A {\n\tx: number;\n\tconstructor(c: boolean) {\n\t\tif (c) {\n\t\t\tthis.x = 1;\n\t\t}\n\t}\n}\n", "field \"x\" is not assigned before the constructor returns");
+	assert_compiles("class A {\n\tx: number;\n\tconstructor(c: boolean) {\n\t\tif (c) {\n\t\t\tthis.x = 1;\n\t\t} else {\n\t\t\tthis.x = 2;\n\t\t}\n\t}\n}\n");
+	// an early return counts as leaving the constructor
+	assert_error("class A {\n\tx: number;\n\ty: number;\n\tconstructor(c: boolean) {\n\t\tif (c) {\n\t\t\treturn;\n\t\t}\n\t\tthis.x = 1;\n\t\tthis.y = 2;\n\t}\n}\n", "fields \"x\", \"y\" are not assigned before the constructor returns");
+	assert_error("class A {\n\tx: number;\n\tconstructor() {\n\t\tthis.x = this.x + 1;\n\t}\n}\n", "field \"x\" is used before being assigned");
+}
+
+#[test]
+fn field_defaults() {
+	assert_compiles("class A {\n\tx: number = 1;\n\ty: string = \"a\";\n}\n");
+	assert_error("class A {\n\tx: number = 1;\n\ty: string;\n}\n", "field \"y\" is not initialized in a class without a constructor");
+	assert_error("class A {\n\tx: number;\n\ty: number;\n}\n", "fields \"x\", \"y\" are not initialized in a class without a constructor");
+	// fields with a default are assigned before the constructor runs
+	assert_compiles("class A {\n\tx: number = 1;\n\ty: number;\n\tconstructor() {\n\t\tthis.y = this.x;\n\t}\n}\n");
+	// and a default can use the fields before it but not the ones after it
+	assert_compiles("class A {\n\tx: number = 1;\n\ty: number = this.x + 1;\n}\n");
+	assert_error("class A {\n\ty: number = this.x + 1;\n\tx: number = 1;\n}\n", "field \"x\" is used before being assigned");
+}
+
+#[test]
+fn super_calls() {
+	assert_compiles(&format!("{}class A extends B {{\n\tx: number;\n\tconstructor() {{\n\t\tsuper(1);\n\t\tthis.x = 1;\n\t}}\n}}\n", BASE));
+	assert_error(&format!("{}class A extends B {{\n\tx: number;\n\tconstructor() {{\n\t\tthis.x = 1;\n\t\tsuper(1);\n\t}}\n}}\n", BASE), "cannot use this before super is called");
+	assert_error(&format!("{}class A extends B {{\n\tx: number;\n\tconstructor() {{\n\t\tthis.x = 1;\n\t}}\n}}\n", BASE), "cannot use this before super is called");
+	assert_error(&format!("{}class A extends B {{\n\tx: number;\n\tconstructor(c: boolean) {{\n\t\tif (c) {{\n\t\t\tsuper(1);\n\t\t}}\n\t\tsuper(2);\n\t\tthis.x = 1;\n\t}}\n}}\n", BASE), "super might already have been called");
+}
+
+#[test]
+fn uses_of_this() {
+	// a method could read any field so all of them have to be assigned first
+	assert_error("class A {\n\tx: number;\n\ty: number;\n\tconstructor() {\n\t\tthis.x = 1;\n\t\tthis.m();\n\t\tthis.y = 2;\n\t}\n\tm() {\n\t}\n}\n", "cannot call method \"m\" because field \"y\" is not assigned yet");
+	assert_compiles("class A {\n\tx: number;\n\ty: number;\n\tconstructor() {\n\t\tthis.x = 1;\n\t\tthis.y = 2;\n\t\tthis.m();\n\t}\n\tm() {\n\t}\n}\n");
+	assert_error("class A {\n\tx: number;\n\tconstructor() {\n\t\tlet a = this;\n\t\tthis.x = 1;\n\t}\n}\n", "cannot use this because field \"x\" is not assigned yet");
+	assert_error("class A {\n\tx: number;\n\tconstructor() {\n\t\tlet f = () => this.x;\n\t\tthis.x = 1;\n\t}\n}\n", "cannot capture this in a lambda because field \"x\" is not assigned yet");
+	// so could a reference to a method, which is bound to this
+	assert_error("class A {\n\ta: number;\n\tb: number;\n\tconstructor() {\n\t\tthis.a = 1;\n\t\tlet g = this.m;\n\t\tg();\n\t\tthis.b = 2;\n\t}\n\tm(): number {\n\t\treturn this.b;\n\t}\n}\n", "cannot reference method \"m\" because field \"b\" is not assigned yet");
+	assert_compiles("class A {\n\ta: number;\n\tb: number;\n\tconstructor() {\n\t\tthis.a = 1;\n\t\tthis.b = 2;\n\t\tlet g = this.m;\n\t\tg();\n\t}\n\tm(): number {\n\t\treturn this.b;\n\t}\n}\n");
+	assert_error(&format!("{}class A extends B {{\n\tconstructor() {{\n\t\tlet g = this.m;\n\t\tsuper(1);\n\t}}\n\tm() {{\n\t}}\n}}\n", BASE), "cannot use this before super is called");
+}
+
+#[test]
+fn default_values() {
+	// the base class hands out this so that the fields can be read before the constructor of A assigns them
+	let source = "class B {\n\tconstructor(register: (B) => void) {\n\t\tregister(this);\n\t}\n}\nclass A extends B {\n\tx: number;\n\tb: boolean;\n\ts: string;\n\tn: B?;\n\tconstructor(register: (B) => void, check: () => void) {\n\t\tsuper(register);\n\t\tcheck();\n\t\tthis.x = 1;\n\t\tthis.b = true;\n\t\tthis.s = \"a\";\n\t\tthis.n = new B(register);\n\t}\n}\n";
+	let expression = "(() => { let a; let seen; new A((b) => { a = b; }, () => { seen = [a.x, a.b, a.s, a.n]; }); return JSON.stringify(seen); })()";
+	assert_eq!(evaluate(source, expression), "[0,false,\"\",null]");
+	assert_eq!(evaluate(source, "JSON.stringify(new A((b) => {}, () => {}), ['x', 'b', 's', 'n'])"), "{\"x\":1,\"b\":true,\"s\":\"a\",\"n\":{}}");
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+const ID: &str = "function id<T>(x: T): T {\n\treturn x;\n}\n";
+
+#[test]
+fn inference() {
+	assert_compiles(&format!("{}function f(): string {{\n\treturn id(\"a\");\n}}\n", ID));
+	assert_error(&format!("{}function f(): number {{\n\treturn id(\"a\");\n}}\n", ID), "type mismatch: expected a Number but found a String");
+	assert_error("function f<T>(): T? {\n\treturn null;\n}\nfunction g() {\n\tlet x = f();\n}\n", "cannot infer type parameter \"T\", it has to be given explicitly");
+	// the expected type of the result is not used for inference
+	assert_error("function f<T>(): T? {\n\treturn null;\n}\nfunction g() {\n\tlet x: number? = f();\n}\n", "cannot infer type parameter \"T\", it has to be given explicitly");
+}
+
+#[test]
+fn explicit_type_arguments() {
+	let js = assert_compiles(&format!("{}function f(): number {{\n\treturn id<number>(1);\n}}\n", ID));
+	assert!(js.contains("return id(1);"), "{}", js);
+	assert_compiles("function f<T>(): T? {\n\treturn null;\n}\nfunction g() {\n\tlet x: number? = f<number>();\n}\n");
+	assert_error(&format!("{}function f(): number {{\n\treturn id<string>(1);\n}}\n", ID), "invalid argument type: expected String but found Number");
+	assert_error(&format!("{}function f(): number {{\n\treturn id<number, string>(1);\n}}\n", ID), "expected 1 type arguments but found 2");
+	// a generic function can be called with type arguments before it is declared
+	assert_compiles(&format!("function f(a: number, b: number): boolean {{\n\treturn id<number>(a) < b;\n}}\n{}", ID));
+	// a variable that shadows a generic function is not generic, so this is a comparison
+	assert_error(&format!("{}function f(id: (number) => number): number {{\n\treturn id<number>(1);\n}}\n", ID), "type mismatch: expected a Number but found a Function([Number], Number)");
+}
+
+#[test]
+fn comparisons() {
+	// a < b > (c) is (a < b) > (c) if a is a local variable and a call with type arguments otherwise
+	assert_error("function f(a: number, b: number, c: number): boolean {\n\treturn a < b > (c);\n}\n", "type mismatch: expected a Number but found a Boolean");
+	assert_error(&format!("{}function f(a: number, b: number): boolean {{\n\treturn a < b > (a);\n}}\n", ID), "type mismatch: expected a Number but found a Boolean");
+	assert_compiles("function g(a: boolean, b: boolean): number {\n\treturn 1;\n}\nfunction f(a: number, b: number): number {\n\treturn g(a < b, a > (b));\n}\n");
+	// local variables and parameters shadow generic functions
+	assert_error(&format!("{}function f(id: number, b: number, c: number): boolean {{\n\treturn id < b > (c);\n}}\n", ID), "type mismatch: expected a Number but found a Boolean");
+	assert_error(&format!("{}function f(b: number, c: number): boolean {{\n\tlet id = 1;\n\treturn id < b > (c);\n}}\n", ID), "type mismatch: expected a Number but found a Boolean");
+	assert_error(&format!("{}function f(b: number, c: number) {{\n\tlet g = (id: number) => id < b > (c);\n}}\n", ID), "type mismatch: expected a Number but found a Boolean");
+	assert_compiles(&format!("{}function g(a: boolean, b: boolean): number {{\n\treturn 1;\n}}\nfunction f(id: number, b: number): number {{\n\treturn g(id < b, id > (b));\n}}\n", ID));
+	// but only where they are visible
+	assert_compiles(&format!("{}function f(a: number, b: number): boolean {{\n\tif (a > b) {{\n\t\tlet id = 1;\n\t}}\n\treturn id<number>(a) < b;\n}}\n", ID));
+	assert_compiles(&format!("{}function f(xs: number[]): number {{\n\tfor (let id of xs) {{\n\t}}\n\treturn id<number>(1);\n}}\n", ID));
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+const BASE: &str = "class A {\n\tx: number = 1;\n\tm(a: number): number {\n\t\treturn a;\n\t}\n}\n";
+
+// a class B that extends A with the given members
+fn derived(members: &str) -> String {
+	format!("{}class B extends A {{\n\t{}\n}}\n", BASE, members)
+}
+
+#[test]
+fn cycles() {
+	assert_error("class A extends A {\n}\n", "class \"A\" inherits from itself");
+	assert_error("class A extends B {\n}\nclass B extends A {\n}\n", "class \"A\" inherits from itself");
+	assert_error("class A extends C {\n}\n", "undefined class \"C\"");
+}
+
+#[test]
+fn overrides() {
+	assert_compiles(&derived("m(a: number): number {\n\t\treturn a + 1;\n\t}"));
+	assert_error(&derived("m(a: string): number {\n\t\treturn 1;\n\t}"), "argument \"a\" of method \"m\" must accept a Number like the method it overrides");
+	assert_error(&derived("m(a: number): string {\n\t\treturn \"a\";\n\t}"), "method \"m\" must return a Number like the method it overrides");
+	assert_error(&derived("m(): number {\n\t\treturn 1;\n\t}"), "method \"m\" must take 1 arguments like the method it overrides");
+	// arguments may be more general and return types more specific
+	assert_compiles("class A {\n\tm(a: A): A? {\n\t\treturn null;\n\t}\n}\nclass B extends A {\n\tm(a: A?): B {\n\t\treturn new B();\n\t}\n}\n");
+	assert_error("class A {\n\tm(a: A?): number {\n\t\treturn 1;\n\t}\n}\nclass B extends A {\n\tm(a: A): number {\n\t\treturn 1;\n\t}\n}\n", "argument \"a\" of method \"m\" must accept a Nullable(Class(\"A\", [])) like the method it overrides");
+	assert_error(&derived("x: number = 2;"), "field \"x\" is already defined in class \"A\"");
+	assert_error(&derived("x() {\n\t}"), "method \"x\" conflicts with a field of class \"A\"");
+}
+
+#[test]
+fn super_calls() {
+	let js = assert_compiles(&derived("m(a: number): number {\n\t\treturn super.m(a) + 1;\n\t}"));
+	assert!(js.contains("return (super.m(a) + 1);"), "{}", js);
+	assert_error(&derived("n(): number {\n\t\treturn super.n();\n\t}"), "class \"A\" does not have a method \"n\"");
+	assert_error("class A {\n\tm(): number {\n\t\treturn super.m();\n\t}\n}\n", "super is only available in methods of a derived class");
+	assert_error("class A {\n\tconstructor() {\n\t\tsuper();\n\t}\n}\n", "super can only be called in the constructor of a derived class");
+	assert_error("class A {\n\tconstructor(n: number) {\n\t}\n}\nclass B extends A {\n\tconstructor() {\n\t\tsuper(\"a\");\n\t}\n}\n", "invalid argument type: expected Number but found String");
+}
+
+#[test]
+fn inherited_members() {
+	assert_compiles(&format!("{}function f(b: B): number {{\n\treturn b.m(b.x) + b.y;\n}}\n", derived("y: number = this.x;")));
+	assert_error(&format!("{}function f(b: B): number {{\n\treturn b.z;\n}}\n", derived("")), "class \"B\" does not have a field \"z\"");
+	assert_error(&format!("{}function f(b: B) {{\n\tb.n();\n}}\n", derived("")), "class \"B\" does not have a method \"n\"");
+	// a derived class can be used where its base class is expected but not the other way around
+	assert_compiles(&format!("{}function f(b: B): A {{\n\treturn b;\n}}\n", derived("")));
+	assert_error(&format!("{}function f(a: A): B {{\n\treturn a;\n}}\n", derived("")), "type mismatch: expected a Class(\"B\", []) but found a Class(\"A\", [])");
+}
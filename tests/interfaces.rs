@@ -0,0 +1,40 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+const INTERFACE: &str = "interface P {\n\tx: number;\n\tm(a: number): string;\n}\n";
+
+// a class A with the given members that claims to implement P
+fn implementation(members: &str) -> String {
+	format!("{}class A implements P {{\n\t{}\n}}\n", INTERFACE, members)
+}
+
+const FIELD: &str = "x: number = 1;";
+const METHOD: &str = "m(a: number): string {\n\t\treturn \"a\";\n\t}";
+
+#[test]
+fn missing_members() {
+	assert_compiles(&implementation(&format!("{}\n\t{}", FIELD, METHOD)));
+	assert_error(&implementation(FIELD), "class \"A\" does not implement interface \"P\": missing method \"m\"");
+	assert_error(&implementation(METHOD), "class \"A\" does not implement interface \"P\": missing field \"x\"");
+	// conformance is structural so a class does not need an implements clause
+	assert_compiles("interface Q {\n\treadonly x: number;\n}\nclass A {\n\tx: number = 1;\n}\nfunction f(a: A): Q {\n\treturn a;\n}\n");
+	assert_error("interface Q {\n\treadonly x: number;\n}\nclass A {\n\ty: number = 1;\n}\nfunction f(a: A): Q {\n\treturn a;\n}\n", "type mismatch: expected a Class(\"Q\", []) but found a Class(\"A\", [])");
+}
+
+#[test]
+fn member_types() {
+	assert_error(&implementation(&format!("x: string = \"a\";\n\t{}", METHOD)), "field \"x\" must have type Number");
+	assert_error(&implementation(&format!("{}\n\tm(a: string): string {{\n\t\treturn a;\n\t}}", FIELD)), "argument \"a\" of method \"m\" must accept a Number like the method in the interface");
+	assert_error(&implementation(&format!("{}\n\tm(a: number): number {{\n\t\treturn a;\n\t}}", FIELD)), "method \"m\" must return a String like the method in the interface");
+	// a field that can be assigned through the interface must have exactly the same type
+	assert_error("interface Q {\n\tx: number?;\n}\nclass A implements Q {\n\tx: number = 1;\n}\n", "field \"x\" must have type Nullable(Number)");
+	assert_compiles("interface Q {\n\treadonly x: number?;\n}\nclass A implements Q {\n\treadonly x: number = 1;\n}\n");
+}
+
+#[test]
+fn readonly_members() {
+	assert_error(&implementation(&format!("readonly {}\n\t{}", FIELD, METHOD)), "field \"x\" must not be readonly");
+	assert_compiles("interface Q {\n\treadonly x: number;\n}\nclass A implements Q {\n\tx: number = 1;\n}\n");
+	assert_error("interface Q {\n\treadonly x: number;\n}\nfunction f(q: Q) {\n\tq.x = 2;\n}\n", "cannot assign to readonly field \"x\" outside of the constructor");
+}
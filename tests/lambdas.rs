@@ -0,0 +1,60 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+const CLASS: &str = "class A {\n\tv: number = 1;\n\tm(): number {\n\t\treturn this.v;\n\t}\n}\n";
+
+#[test]
+fn return_types() {
+	assert_compiles("function f() {\n\tlet g = (): void => {\n\t\tlet x = 1;\n\t};\n\tg();\n}\n");
+	assert_compiles("function f(): (number) => void {\n\treturn (x: number): void => {\n\t};\n}\n");
+	assert_error("function f() {\n\tlet g = (): void => {\n\t\treturn 1;\n\t};\n}\n", "cannot return a value from a function without a return type");
+	assert_compiles("function f(): number {\n\tlet g = (x: number): number => {\n\t\treturn x;\n\t};\n\treturn g(1);\n}\n");
+	assert_error("function f() {\n\tlet g = (x: number): string => x;\n}\n", "type mismatch: expected a String but found a Number");
+}
+
+#[test]
+fn inferred_return_types() {
+	assert_compiles("function f(): number {\n\tlet g = (x: number) => x + 1;\n\treturn g(1);\n}\n");
+	// the return type of a block body is inferred from its return statements
+	assert_compiles("function f(): number {\n\tlet g = (x: number) => {\n\t\treturn x;\n\t};\n\treturn g(1);\n}\n");
+	assert_error("function f(): string {\n\tlet g = (x: number) => {\n\t\treturn x;\n\t};\n\treturn g(1);\n}\n", "type mismatch: expected a String but found a Number");
+	assert_error("function f() {\n\tlet g = () => {\n\t\tlet x = 1;\n\t};\n\tlet y: number = g();\n}\n", "type mismatch: expected a Number but found a Void");
+	assert_compiles(&format!("{}function f(): A? {{\n\tlet g = (c: boolean) => {{\n\t\tif (c) {{\n\t\t\treturn null;\n\t\t}}\n\t\treturn new A();\n\t}};\n\treturn g(true);\n}}\n", CLASS));
+	assert_error("function f() {\n\tlet g = (c: boolean) => {\n\t\tif (c) {\n\t\t\treturn 1;\n\t\t}\n\t\treturn \"a\";\n\t};\n}\n", "cannot infer the return type of a lambda that returns a Number and a String");
+	assert_error("function f() {\n\tlet g = (c: boolean) => {\n\t\tif (c) {\n\t\t\treturn;\n\t\t}\n\t\treturn 1;\n\t};\n}\n", "a lambda without a return type must either always or never return a value");
+	assert_error("function f() {\n\tlet g = () => {\n\t\treturn null;\n\t};\n}\n", "cannot infer the return type of a lambda that returns null");
+	assert_error("function f() {\n\tlet g = (c: boolean) => {\n\t\tif (c) {\n\t\t\treturn 1;\n\t\t}\n\t};\n}\n", "not all paths of the lambda return a value");
+	// a nested lambda has its own return type
+	assert_compiles("function f(): number {\n\tlet g = (x: number) => {\n\t\tlet h = () => {\n\t\t\treturn \"a\";\n\t\t};\n\t\treturn x;\n\t};\n\treturn g(1);\n}\n");
+}
+
+#[test]
+fn closures() {
+	let js = assert_compiles("function counter(): () => number {\n\tlet n = 0;\n\treturn () => {\n\t\tn++;\n\t\treturn n;\n\t};\n}\n");
+	assert!(js.contains("\treturn (() => {\n\t\t(n++);\n\t\treturn n;\n\t});"), "{}", js);
+	assert_compiles("function f(): number {\n\tlet n = 0;\n\tlet add = (x: number) => {\n\t\tn += x;\n\t};\n\tadd(2);\n\treturn n;\n}\n");
+	// a lambda body cannot leave a loop that surrounds the lambda
+	assert_error("function f() {\n\twhile (true) {\n\t\tlet g = () => {\n\t\t\tbreak;\n\t\t};\n\t}\n}\n", "break outside of a loop");
+}
+
+#[test]
+fn captured_variables() {
+	// a narrowed variable is not narrowed inside of a lambda because it might be assigned null before the call
+	assert_error(&format!("{}function f(x: A?): number {{\n\tif (x != null) {{\n\t\tlet g = () => x.v;\n\t\tx = null;\n\t\treturn g();\n\t}}\n\treturn 0;\n}}\n", CLASS), "trying to access a property on a value that might be null");
+	// but the narrowing continues after the lambda
+	assert_compiles(&format!("{}function f(x: A?): number {{\n\tif (x != null) {{\n\t\tlet g = () => 1;\n\t\treturn x.v + g();\n\t}}\n\treturn 0;\n}}\n", CLASS));
+	assert_compiles(&format!("{}function f(x: A?): number {{\n\tif (x != null) {{\n\t\tconst y = x;\n\t\tlet g = () => y.v;\n\t\treturn g();\n\t}}\n\treturn 0;\n}}\n", CLASS));
+}
+
+#[test]
+fn method_binding() {
+	let js = assert_compiles(&format!("{}function f(a: A): number {{\n\tlet g = a.m;\n\treturn g();\n}}\n", CLASS));
+	assert!(js.contains("let g = a.m.bind(a);"), "{}", js);
+	// the object is only evaluated once
+	let js = assert_compiles(&format!("{}function make(): A {{\n\treturn new A();\n}}\nfunction f(): number {{\n\tlet g = make().m;\n\treturn g();\n}}\n", CLASS));
+	assert!(js.contains("let g = ((object) => object.m.bind(object))(make());"), "{}", js);
+	// fields are not bound
+	let js = assert_compiles(&format!("{}function f(a: A): number {{\n\treturn a.v;\n}}\n", CLASS));
+	assert!(js.contains("return a.v;"), "{}", js);
+}
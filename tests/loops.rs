@@ -0,0 +1,75 @@
+mod common;
+
+use common::{assert_compiles, assert_error, evaluate};
+
+#[test]
+fn arrays() {
+	let js = assert_compiles("function f(xs: number[]): number {\n\tlet s = 0;\n\tfor (let x of xs) {\n\t\ts += x;\n\t}\n\treturn s;\n}\n");
+	assert!(js.contains("\tfor (let x of xs)\n\t\t{\n\t\t\t(s += x);\n\t\t}\n"), "{}", js);
+	assert_compiles("function f(xs: string[]): string {\n\tlet s = \"\";\n\tfor (const x of xs) {\n\t\ts = s + x;\n\t}\n\treturn s;\n}\n");
+	assert_error("function f() {\n\tfor (let x of [1, 2]) {\n\t\tlet y: string = x;\n\t}\n}\n", "type mismatch: expected a String but found a Number");
+	assert_error("function f(xs: number[]) {\n\tfor (const x of xs) {\n\t\tx = 1;\n\t}\n}\n", "cannot assign to constant \"x\"");
+	assert_error("function f(xs: number[]?) {\n\tfor (let x of xs) {\n\t}\n}\n", "trying to iterate over a value that might be null");
+	assert_error("function f(x: number) {\n\tfor (let i of x) {\n\t}\n}\n", "cannot iterate over a Number");
+}
+
+#[test]
+fn ranges() {
+	let js = assert_compiles("function f(n: number): number {\n\tlet s = 0;\n\tfor (let i of 1..n + 1) {\n\t\ts += i;\n\t}\n\treturn s;\n}\n");
+	assert!(js.contains("\tfor (let $i = 1, $end = (n + 1); $i < $end; $i++)\n\t\t{\n\t\t\tlet i = $i;\n\t\t\t{\n\t\t\t\t(s += i);\n\t\t\t}\n\t\t}\n"), "{}", js);
+	// the loop variable is a copy of the counter so assigning to it does not change the iteration
+	let js = assert_compiles("function f() {\n\tfor (let i of 0..3) {\n\t\ti = 5;\n\t}\n}\n");
+	assert!(js.contains("let i = $i;\n\t\t\t{\n\t\t\t\t(i = 5);"), "{}", js);
+	assert_error("function f() {\n\tfor (const i of 0..3) {\n\t\ti = 5;\n\t}\n}\n", "cannot assign to constant \"i\"");
+	assert_error("function f(x: string) {\n\tfor (let i of 0..x) {\n\t}\n}\n", "type mismatch: expected a Number but found a String");
+}
+
+#[test]
+fn labels() {
+	let js = assert_compiles("function f(n: number): number {\n\tlet s = 0;\n\touter: for (let i of 0..n) {\n\t\tfor (const j of 1..i + 1) {\n\t\t\tif (j == 3) {\n\t\t\t\tcontinue outer;\n\t\t\t}\n\t\t\tif (i == 5) {\n\t\t\t\tbreak outer;\n\t\t\t}\n\t\t\ts += j;\n\t\t}\n\t}\n\treturn s;\n}\n");
+	// the label stays on the loop and not on the block around the body so that continue works
+	assert!(js.contains("\touter:\n\tfor (let $i = 0, $end = n; $i < $end; $i++)\n"), "{}", js);
+	assert!(js.contains("continue outer;"), "{}", js);
+	assert!(js.contains("break outer;"), "{}", js);
+	assert_compiles("function f(xs: number[]) {\n\tloop: for (let x of xs) {\n\t\tif (x > 1) {\n\t\t\tbreak loop;\n\t\t}\n\t\tcontinue loop;\n\t}\n}\n");
+	assert_error("function f() {\n\tfor (let i of 0..3) {\n\t\tbreak outer;\n\t}\n}\n", "undefined label \"outer\"");
+}
+
+#[test]
+fn for_loops() {
+	let source = "function f(n: number): number {\n\tlet s = 0;\n\tfor (let i = 0; i < n; i++) {\n\t\ts += i;\n\t}\n\treturn s;\n}\n";
+	assert!(assert_compiles(source).contains("\tfor (let i = 0; (i < n); (i++))\n"));
+	assert_eq!(evaluate(source, "f(5)"), "10");
+	// the update also runs after a continue
+	let source = "function f(n: number): number {\n\tlet s = 0;\n\tfor (let i = 0; i < n; i++) {\n\t\tif (i % 2 == 0) {\n\t\t\tcontinue;\n\t\t}\n\t\ts += i;\n\t}\n\treturn s;\n}\n";
+	assert_eq!(evaluate(source, "f(10)"), "25");
+	// every part of the header is optional
+	let source = "function f(): number {\n\tlet i = 0;\n\tfor (;;) {\n\t\tif (i == 4) {\n\t\t\tbreak;\n\t\t}\n\t\ti++;\n\t}\n\treturn i;\n}\n";
+	assert_eq!(evaluate(source, "f()"), "4");
+	// the variables of the initializer are only visible inside of the loop
+	assert_error("function f(): number {\n\tfor (let i = 0; i < 3; i++) {\n\t}\n\treturn i;\n}\n", "undefined variable \"i\"");
+	let source = "function f(): number {\n\tfor (let i = 0; i < 3; i++) {\n\t}\n\tlet i = 10;\n\treturn i;\n}\n";
+	assert_eq!(evaluate(source, "f()"), "10");
+}
+
+#[test]
+fn do_while_loops() {
+	// the body runs once before the condition is checked
+	let source = "function f(n: number): number {\n\tlet i = 0;\n\tdo {\n\t\ti++;\n\t} while (i < n);\n\treturn i;\n}\n";
+	let js = assert_compiles(source);
+	assert!(js.contains("\tdo\n\t\t{\n\t\t\t(i++);\n\t\t}\n\twhile ((i < n));\n"), "{}", js);
+	assert_eq!(evaluate(source, "f(3)"), "3");
+	assert_eq!(evaluate(source, "f(0)"), "1");
+	// continue jumps to the condition
+	let source = "function f(): number {\n\tlet i = 0;\n\tlet s = 0;\n\tdo {\n\t\ti++;\n\t\tif (i == 2) {\n\t\t\tcontinue;\n\t\t}\n\t\ts += i;\n\t} while (i < 4);\n\treturn s;\n}\n";
+	assert_eq!(evaluate(source, "f()"), "8");
+}
+
+#[test]
+fn break_and_continue() {
+	assert_error("function f() {\n\tbreak;\n}\n", "break outside of a loop");
+	assert_error("function f() {\n\tcontinue;\n}\n", "continue outside of a loop");
+	assert_error("function f(c: boolean) {\n\tif (c) {\n\t\tcontinue;\n\t}\n}\n", "continue outside of a loop");
+	let source = "function f(): number {\n\tlet i = 0;\n\twhile (true) {\n\t\ti++;\n\t\tif (i < 3) {\n\t\t\tcontinue;\n\t\t}\n\t\tbreak;\n\t}\n\treturn i;\n}\n";
+	assert_eq!(evaluate(source, "f()"), "3");
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+#[test]
+fn methods() {
+	let js = assert_compiles("function f(m: Map<string, number>): number {\n\tm.set(\"a\", 1).set(\"b\", 2);\n\tif (m.has(\"b\")) {\n\t\tm.delete(\"b\");\n\t}\n\treturn m.size;\n}\n");
+	assert!(js.contains("m.set(\"a\", 1).set(\"b\", 2);"), "{}", js);
+	assert!(js.contains("return m.size;"), "{}", js);
+	assert_compiles("function f(m: Map<string, number>): boolean {\n\treturn m.delete(\"a\");\n}\n");
+	assert_error("function f(m: Map<string, number>) {\n\tm.clear();\n}\n", "maps do not have a method \"clear\"");
+	assert_error("function f(m: Map<string, number>?) {\n\tm.get(\"a\");\n}\n", "trying to access a property on a value that might be null");
+}
+
+#[test]
+fn argument_types() {
+	assert_error("function f(m: Map<string, number>) {\n\tm.set(1, 2);\n}\n", "type mismatch: expected a String but found a Number");
+	assert_error("function f(m: Map<string, number>) {\n\tm.set(\"a\", \"b\");\n}\n", "type mismatch: expected a Number but found a String");
+	assert_error("function f(m: Map<string, number>) {\n\tm.has(1);\n}\n", "type mismatch: expected a String but found a Number");
+}
+
+#[test]
+fn size() {
+	assert_error("function f(m: Map<string, number>) {\n\tm.size = 2;\n}\n", "cannot assign to the size of a map");
+}
+
+#[test]
+fn get() {
+	// a missing key gives null instead of undefined
+	let js = assert_compiles("function f(m: Map<string, number>): number {\n\tlet x = m.get(\"a\");\n\tif (x != null) {\n\t\treturn x;\n\t}\n\treturn 0;\n}\n");
+	assert!(js.contains("let x = (m.get(\"a\") ?? null);"), "{}", js);
+	assert_error("function f(m: Map<string, number>): number {\n\treturn m.get(\"a\");\n}\n", "type mismatch: expected a Number but found a Nullable(Number)");
+	assert_compiles("function f(m: Map<string, number>): number? {\n\treturn m.get(\"a\");\n}\n");
+	// the result is not nullable twice if the values already are
+	assert_compiles("function f(m: Map<string, number?>): number? {\n\treturn m.get(\"a\");\n}\n");
+}
+
+#[test]
+fn instantiation() {
+	let js = assert_compiles("function f(): number {\n\tlet m = new Map<string, number>();\n\treturn m.size;\n}\n");
+	assert!(js.contains("let m = new Map();"), "{}", js);
+	assert_compiles("function f() {\n\tlet m: Map<string, number> = new Map();\n}\n");
+	assert_error("function f() {\n\tlet m = new Map();\n}\n", "cannot infer the type arguments of a Map, they have to be given explicitly");
+	assert_error("function f() {\n\tlet m = new Map<string>();\n}\n", "Map expects 2 type arguments but found 1");
+}
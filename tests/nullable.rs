@@ -0,0 +1,76 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+const CLASS: &str = "class A {\n\tv: number = 1;\n\tm(): number {\n\t\treturn this.v;\n\t}\n}\n";
+const ERROR: &str = "trying to access a property on a value that might be null";
+
+// a function with a nullable argument x and a boolean argument c that can be used for conditions
+fn function(body: &str) -> String {
+	format!("{}function f(x: A?, c: boolean): number {{\n\t{}\n\treturn 0;\n}}\n", CLASS, body)
+}
+
+#[test]
+fn nullable_receiver() {
+	assert_error(&function("let y = x.v;"), ERROR);
+	assert_error(&function("x.v = 2;"), ERROR);
+	assert_error(&function("let y = x.m();"), ERROR);
+	assert_error(&format!("{}class B {{\n\ta: A? = null;\n\tm(): number {{\n\t\tif (this.a != null) {{\n\t\t\treturn this.a.v;\n\t\t}}\n\t\treturn 0;\n\t}}\n}}\n", CLASS), ERROR);
+}
+
+#[test]
+fn conditions() {
+	assert_compiles(&function("if (x != null) {\n\t\treturn x.v;\n\t}"));
+	assert_compiles(&function("if (null != x) {\n\t\treturn x.v;\n\t}"));
+	assert_compiles(&function("if (x == null) {\n\t} else {\n\t\treturn x.m();\n\t}"));
+	assert_compiles(&function("return x != null ? x.v : 0;"));
+	assert_error(&function("if (x == null) {\n\t\treturn x.v;\n\t}"), ERROR);
+	// the narrowing ends with the branch
+	assert_error(&function("if (x != null) {\n\t}\n\treturn x.v;"), ERROR);
+	assert_compiles(&function("while (x != null) {\n\t\tlet y = x.v;\n\t\tx = null;\n\t}"));
+}
+
+#[test]
+fn early_returns() {
+	assert_compiles(&function("if (x == null) {\n\t\treturn 0;\n\t}\n\treturn x.v;"));
+	assert_compiles(&function("if (x == null)\n\t\treturn 1;\n\treturn x.m();"));
+	assert_error(&function("if (x == null) {\n\t\tlet y = 1;\n\t}\n\treturn x.v;"), ERROR);
+	assert_error(&function("if (x == null && c) {\n\t\treturn 0;\n\t}\n\treturn x.v;"), ERROR);
+}
+
+#[test]
+fn logical_operators() {
+	assert_compiles(&function("let b = x != null && x.v > 0;"));
+	assert_compiles(&function("let b = x == null || x.v > 0;"));
+	assert_compiles(&function("if (x != null && c) {\n\t\treturn x.v;\n\t}"));
+	assert_compiles(&function("if (x == null || c) {\n\t\treturn 0;\n\t}\n\treturn x.v;"));
+	assert_error(&function("let b = x != null || x.v > 0;"), ERROR);
+	assert_error(&function("let b = x == null && x.v > 0;"), ERROR);
+	assert_error(&function("if (x != null || c) {\n\t\treturn x.v;\n\t}"), ERROR);
+	// an assignment in a later operand undoes the narrowing of an earlier one
+	assert_error(&format!("{}function g(): A? {{\n\treturn null;\n}}\nfunction f(x: A?): number {{\n\tif (x != null && (x = g()) == null) {{\n\t\treturn x.v;\n\t}}\n\treturn 0;\n}}\n", CLASS), ERROR);
+	assert_error(&function("if (x == null || (x = null) == null) {\n\t\treturn 0;\n\t}\n\treturn x.v;"), ERROR);
+	assert_compiles(&function("if ((x = new A()) != null && x != null) {\n\t\treturn x.v;\n\t}"));
+}
+
+#[test]
+fn assignments_in_loops() {
+	// an assignment later in the loop body reaches the start of the next iteration
+	assert_error(&function("if (x != null) {\n\t\twhile (c) {\n\t\t\tlet y = x.v;\n\t\t\tx = null;\n\t\t}\n\t}"), ERROR);
+	assert_error(&function("if (x != null) {\n\t\tfor (let i = 0; i < 3; i++) {\n\t\t\tlet y = x.v;\n\t\t\tif (c) {\n\t\t\t\tx = null;\n\t\t\t}\n\t\t}\n\t}"), ERROR);
+	assert_compiles(&function("if (x != null) {\n\t\tfor (let i = 0; i < 3; i++) {\n\t\t\tlet y = x.v;\n\t\t}\n\t}"));
+}
+
+#[test]
+fn captured_variables() {
+	// a lambda can be called after the variable has been set to null again
+	assert_error(&function("if (x != null) {\n\t\tlet g = () => x.v;\n\t\tx = null;\n\t\treturn g();\n\t}"), ERROR);
+	assert_error(&function("if (x != null) {\n\t\tlet g = () => x.v;\n\t\treturn g();\n\t}"), ERROR);
+	// and an assignment inside of a lambda can happen at any call
+	assert_error(&function("if (x != null) {\n\t\tlet g = () => {\n\t\t\tx = null;\n\t\t};\n\t\tg();\n\t\treturn x.v;\n\t}"), ERROR);
+	// even if the lambda was created before the narrowing
+	assert_error(&function("let g = () => {\n\t\tx = null;\n\t};\n\tif (x != null) {\n\t\tg();\n\t\treturn x.v;\n\t}"), ERROR);
+	assert_error(&function("let g = () => {\n\t};\n\twhile (c) {\n\t\tif (x != null) {\n\t\t\tg();\n\t\t\treturn x.v;\n\t\t}\n\t\tg = () => {\n\t\t\tx = null;\n\t\t};\n\t}"), ERROR);
+	// copying the narrowed value into a variable that is never null works
+	assert_compiles(&function("if (x != null) {\n\t\tlet y = x;\n\t\tlet g = () => y.v;\n\t\treturn g();\n\t}"));
+}
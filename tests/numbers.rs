@@ -0,0 +1,72 @@
+mod common;
+
+// compiles a variable declaration with a number literal and returns the generated JS for the literal
+fn compile_number(literal: &str) -> Result<String, String> {
+	let js = common::compile(&format!("function f() {{\n\tlet x = {};\n}}\n", literal))?;
+	let statement = js.lines().nth(1).unwrap().trim();
+	Ok(statement.strip_prefix("let x = ").unwrap().strip_suffix(';').unwrap().to_string())
+}
+
+fn assert_number(literal: &str, expected: &str) {
+	match compile_number(literal) {
+		Ok(js) => assert_eq!(js, expected, "while compiling {}", literal),
+		Err(e) => panic!("failed to compile {}: {}", literal, e),
+	}
+}
+
+fn assert_number_error(literal: &str, expected: &str) {
+	match compile_number(literal) {
+		Ok(js) => panic!("{} compiled to {} but an error was expected", literal, js),
+		Err(e) => assert!(e.contains(expected), "unexpected error while compiling {}: {}", literal, e),
+	}
+}
+
+#[test]
+fn decimal_literals() {
+	assert_number("0", "0");
+	assert_number("0.5", "0.5");
+	assert_number("1e+3", "1000");
+	assert_number("2E-3", "0.002");
+	assert_number("1e21", "1e21");
+	assert_number("1e-7", "1e-7");
+	assert_number("5e-324", "5e-324");
+	assert_number("0e-400", "0");
+	assert_number_error("012", "number literals must not have leading zeros");
+	assert_number_error("00", "number literals must not have leading zeros");
+	assert_number_error("1e", "malformed exponent in number literal");
+	assert_number_error("12abc", "invalid character in number literal");
+}
+
+#[test]
+fn radix_literals() {
+	assert_number("0x1F", "31");
+	assert_number("0XfF", "255");
+	assert_number("0o17", "15");
+	assert_number("0b101", "5");
+	assert_number_error("0x", "expected a digit");
+	assert_number_error("0o", "expected a digit");
+	assert_number_error("0b102", "invalid character in number literal");
+	assert_number_error("0o8", "expected a digit");
+	assert_number_error("0xg", "expected a digit");
+}
+
+#[test]
+fn numeric_separators() {
+	assert_number("1_000", "1000");
+	assert_number("0xf_f", "255");
+	assert_number("1.000_5", "1.0005");
+	assert_number_error("1__0", "numeric separators are only allowed between digits");
+	assert_number_error("1_", "numeric separators are only allowed between digits");
+	assert_number_error("0x_f", "numeric separators are only allowed between digits");
+	assert_number_error("1_.5", "numeric separators are only allowed between digits");
+	assert_number_error("0_1", "number literals must not have leading zeros");
+}
+
+#[test]
+fn out_of_range() {
+	assert_number_error("1e400", "number literal out of range");
+	assert_number_error("0xffffffffffffffffffffffffffffffffffffff", "number literal out of range");
+	// underflow is rejected as well instead of silently becoming 0
+	assert_number_error("1e-400", "number literal out of range");
+	assert_number_error("0.000_1e-400", "number literal out of range");
+}
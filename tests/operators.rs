@@ -0,0 +1,181 @@
+mod common;
+
+// compiles an expression statement with the variables a, b and c of the given type and returns the generated JS expression
+fn compile_expression(ty: &str, expression: &str) -> Result<String, String> {
+	let source = format!("function f(a: {0}, b: {0}, c: {0}) {{\n\t{1};\n}}\n", ty, expression);
+	let js = common::compile(&source)?;
+	let statement = js.lines().nth(1).unwrap().trim();
+	Ok(statement.strip_suffix(';').unwrap().to_string())
+}
+
+fn assert_js(ty: &str, expression: &str, expected: &str) {
+	match compile_expression(ty, expression) {
+		Ok(js) => assert_eq!(js, expected, "while compiling {}", expression),
+		Err(e) => panic!("failed to compile {}: {}", expression, e),
+	}
+}
+
+fn assert_error(ty: &str, expression: &str, expected: &str) {
+	match compile_expression(ty, expression) {
+		Ok(js) => panic!("{} compiled to {} but an error was expected", expression, js),
+		Err(e) => assert!(e.contains(expected), "unexpected error while compiling {}: {}", expression, e),
+	}
+}
+
+#[test]
+fn assignment() {
+	assert_js("number", "a = b", "(a = b)");
+	assert_js("number", "a = b = c", "(a = (b = c))");
+	assert_js("number", "a = b + c", "(a = (b + c))");
+	assert_js("boolean", "a = b || c", "(a = (b || c))");
+	assert_js("number[]", "a[0] = b[1]", "(a[0] = b[1])");
+	assert_error("number", "a + b = c", "left hand of an assignment must be a name, a property access or an index expression");
+	assert_error("number", "a = true", "type mismatch");
+}
+
+#[test]
+fn compound_assignment() {
+	assert_js("number", "a += b", "(a += b)");
+	assert_js("number", "a -= b", "(a -= b)");
+	assert_js("number", "a *= b", "(a *= b)");
+	assert_js("number", "a /= b", "(a /= b)");
+	assert_js("number", "a %= b", "(a %= b)");
+	assert_js("string", "a += b", "(a += b)");
+	assert_js("boolean", "a &&= b", "(a &&= b)");
+	assert_js("boolean", "a ||= b", "(a ||= b)");
+	assert_js("number", "a += b -= c", "(a += (b -= c))");
+	assert_js("number", "a = b += c", "(a = (b += c))");
+	assert_js("number", "a -= b - c", "(a -= (b - c))");
+	assert_js("boolean", "a ||= b && c", "(a ||= (b && c))");
+	assert_js("number[]", "a[0] += b[c.length]", "(a[0] += b[c.length])");
+	assert_error("number", "a + b += c", "left hand of an assignment must be a name, a property access or an index expression");
+	assert_error("string", "a -= b", "invalid compound assignment");
+	assert_error("boolean", "a += b", "invalid compound assignment");
+	assert_error("number", "a &&= b", "invalid compound assignment");
+	assert_error("number", "a += true", "type mismatch");
+}
+
+#[test]
+fn conditional_operator() {
+	assert_js("boolean", "a ? b : c", "(a ? b : c)");
+	assert_js("boolean", "a ? b : c ? a : b", "(a ? b : (c ? a : b))");
+	assert_js("boolean", "a ? b ? c : a : b", "(a ? (b ? c : a) : b)");
+	assert_js("boolean", "a || b ? b && c : !a", "((a || b) ? (b && c) : !a)");
+	assert_js("boolean", "a = b ? c : a", "(a = (b ? c : a))");
+	assert_js("boolean", "a ? b = c : a", "(a ? (b = c) : a)");
+	assert_js("number", "a < b ? a : b", "((a < b) ? a : b)");
+	assert_error("number", "a ? b : c", "type mismatch");
+	assert_js("number?", "a != null ? a : null", "((a !== null) ? a : null)");
+	assert_error("boolean", "a ? 1 : b", "branches of a conditional expression must have the same type");
+}
+
+#[test]
+fn logical_operators() {
+	assert_js("boolean", "a || b", "(a || b)");
+	assert_js("boolean", "a && b", "(a && b)");
+	assert_js("boolean", "a || b || c", "((a || b) || c)");
+	assert_js("boolean", "a && b && c", "((a && b) && c)");
+	assert_js("boolean", "a || b && c", "(a || (b && c))");
+	assert_js("boolean", "a && b || c", "((a && b) || c)");
+	assert_error("number", "a || b", "type mismatch");
+	assert_error("number", "a && b", "type mismatch");
+}
+
+#[test]
+fn equality_operators() {
+	assert_js("number", "a == b", "(a === b)");
+	assert_js("number", "a != b", "(a !== b)");
+	assert_js("string", "a == b", "(a === b)");
+	assert_js("number", "a + b == c", "((a + b) === c)");
+	assert_js("number", "a == b && b != c", "((a === b) && (b !== c))");
+	assert_js("number", "a == b || a < c", "((a === b) || (a < c))");
+	assert_js("boolean", "a == false", "(a === false)");
+	assert_js("boolean", "a != b == c", "((a !== b) === c)");
+	assert_js("number", "a < b == b < c", "((a < b) === (b < c))");
+	assert_error("number", "a == true", "cannot compare a Number with a Boolean");
+	assert_error("string", "a != 1", "cannot compare a String with a Number");
+	assert_js("number?", "a == null", "(a === null)");
+	assert_js("number?", "null != a", "(null !== a)");
+	assert_js("number?", "a == b", "(a === b)");
+	assert_error("number", "a == null", "cannot compare a Number with a Null");
+}
+
+#[test]
+fn reference_equality() {
+	// class instances are equal only if they are the same instance
+	let source = "class A {\n\tx: number = 1;\n}\nfunction same(): boolean {\n\tlet a = new A();\n\tlet b = a;\n\treturn a == b;\n}\nfunction distinct(): boolean {\n\treturn new A() == new A();\n}\nfunction different(): boolean {\n\tlet a = new A();\n\treturn a != new A();\n}\n";
+	assert_eq!(common::evaluate(source, "same()"), "true");
+	assert_eq!(common::evaluate(source, "distinct()"), "false");
+	assert_eq!(common::evaluate(source, "different()"), "true");
+}
+
+#[test]
+fn relational_operators() {
+	assert_js("number", "a < b", "(a < b)");
+	assert_js("number", "a <= b", "(a <= b)");
+	assert_js("number", "a > b", "(a > b)");
+	assert_js("number", "a >= b", "(a >= b)");
+	assert_js("number", "a + b < c * a", "((a + b) < (c * a))");
+	assert_js("number", "a >= b && b > c", "((a >= b) && (b > c))");
+	assert_error("boolean", "a < b", "type mismatch");
+	assert_error("string", "a >= b", "type mismatch");
+}
+
+#[test]
+fn additive_operators() {
+	assert_js("number", "a + b", "(a + b)");
+	assert_js("number", "a - b", "(a - b)");
+	assert_js("string", "a + b", "(a + b)");
+	assert_js("number", "a - b - c", "((a - b) - c)");
+	assert_js("number", "a - b + c", "((a - b) + c)");
+	assert_js("number", "a - (b - c)", "(a - (b - c))");
+	assert_error("boolean", "a + b", "type mismatch");
+	assert_error("string", "a - b", "type mismatch");
+}
+
+#[test]
+fn multiplicative_operators() {
+	assert_js("number", "a * b", "(a * b)");
+	assert_js("number", "a / b", "(a / b)");
+	assert_js("number", "a % b", "(a % b)");
+	assert_js("number", "a / b * c", "((a / b) * c)");
+	assert_js("number", "a % b / c", "((a % b) / c)");
+	assert_js("number", "a + b * c", "(a + (b * c))");
+	assert_js("number", "a * b - c", "((a * b) - c)");
+	assert_js("number", "(a + b) * c", "((a + b) * c)");
+	assert_error("string", "a * b", "type mismatch");
+}
+
+#[test]
+fn prefix_operators() {
+	assert_js("boolean", "!a", "!a");
+	assert_js("boolean", "!!a", "!!a");
+	assert_js("boolean", "!a && b", "(!a && b)");
+	assert_js("number", "-a", "(-a)");
+	assert_js("number", "+a", "(+a)");
+	assert_js("number", "- -a", "(-(-a))");
+	assert_js("number", "-+a", "(-(+a))");
+	assert_js("number", "a - -b", "(a - (-b))");
+	assert_js("number", "a + +b", "(a + (+b))");
+	assert_js("number", "-a * b", "((-a) * b)");
+	assert_error("number", "!a", "type mismatch");
+	assert_error("boolean", "-a", "type mismatch");
+}
+
+#[test]
+fn increment_and_decrement() {
+	assert_js("number", "++a", "(++a)");
+	assert_js("number", "--a", "(--a)");
+	assert_js("number", "a++", "(a++)");
+	assert_js("number", "a--", "(a--)");
+	assert_js("number", "-a++", "(-(a++))");
+	assert_js("number", "- --a", "(-(--a))");
+	assert_js("number", "a++ + b", "((a++) + b)");
+	assert_js("number", "a - --b", "(a - (--b))");
+	assert_js("number", "a-- * b", "((a--) * b)");
+	assert_js("number", "a = b++", "(a = (b++))");
+	assert_js("number[]", "a[b.length]++", "(a[b.length]++)");
+	assert_error("number", "++(a + b)", "operand of an increment or decrement must be a name, a property access or an index expression");
+	assert_error("number", "a++++", "operand of an increment or decrement must be a name, a property access or an index expression");
+	assert_error("boolean", "a++", "type mismatch");
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+#[test]
+fn return_values() {
+	assert_compiles("function f(): number {\n\treturn 1;\n}\n");
+	assert_error("function f(): number {\n\treturn true;\n}\n", "type mismatch: expected a Number but found a Boolean");
+	assert_compiles("class A {\n\tm(): number {\n\t\treturn 1;\n\t}\n}\n");
+	assert_error("class A {\n\tm(): number {\n\t\treturn \"a\";\n\t}\n}\n", "type mismatch: expected a Number but found a String");
+}
+
+#[test]
+fn missing_return_values() {
+	assert_error("function f(): number {\n\treturn;\n}\n", "expected a return value of type Number");
+	assert_error("function f(x: boolean): boolean {\n\tif (x) {\n\t\treturn;\n\t}\n\treturn x;\n}\n", "expected a return value of type Boolean");
+	assert_error("class A {\n\tm(): string {\n\t\treturn;\n\t}\n}\n", "expected a return value of type String");
+}
+
+#[test]
+fn functions_without_return_type() {
+	assert_compiles("function f() {\n\treturn;\n}\n");
+	assert_error("function f() {\n\treturn 1;\n}\n", "cannot return a value from a function without a return type");
+	assert_compiles("class A {\n\tm() {\n\t\treturn;\n\t}\n}\n");
+	assert_error("class A {\n\tm() {\n\t\treturn 1;\n\t}\n}\n", "cannot return a value from a function without a return type");
+	assert_error("class A {\n\tconstructor() {\n\t\treturn 1;\n\t}\n}\n", "cannot return a value from a function without a return type");
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::{assert_compiles, assert_error};
+
+#[test]
+fn escapes() {
+	let js = assert_compiles("function f(): string {\n\treturn \"a\\nb\\tc\\\\d\\\"e\";\n}\n");
+	assert!(js.contains("return \"a\\nb\\tc\\\\d\\\"e\";"), "{}", js);
+	// a single quote does not need to be escaped in the output
+	let js = assert_compiles("function f(): string {\n\treturn \"\\'\";\n}\n");
+	assert!(js.contains("return \"'\";"), "{}", js);
+	let js = assert_compiles("function f(): string {\n\treturn \"\\u{48}\\u{e9}\\u{1F600}\";\n}\n");
+	assert!(js.contains("return \"Hé\u{1F600}\";"), "{}", js);
+	// control characters and line separators are escaped in the output
+	let js = assert_compiles("function f(): string {\n\treturn \"\\0\\u{1}\\u{2028}\";\n}\n");
+	assert!(js.contains("return \"\\u0000\\u0001\\u2028\";"), "{}", js);
+}
+
+#[test]
+fn invalid_escapes() {
+	assert_error("function f(): string {\n\treturn \"\\q\";\n}\n", "invalid escape sequence");
+	assert_error("function f(): string {\n\treturn \"\\u{110000}\";\n}\n", "invalid unicode escape sequence");
+	assert_error("function f(): string {\n\treturn \"\\u{}\";\n}\n", "invalid unicode escape sequence");
+}
+
+#[test]
+fn unterminated_strings() {
+	assert_error("function f(): string {\n\treturn \"abc;\n}\n", "unterminated string literal");
+	assert_error("function f(): string {\n\treturn \"abc", "unterminated string literal");
+}
+
+#[test]
+fn concatenation() {
+	let js = assert_compiles("function f(a: string): string {\n\treturn a + \"b\";\n}\n");
+	assert!(js.contains("return (a + \"b\");"), "{}", js);
+	assert_error("function f(a: string): string {\n\treturn a + 1;\n}\n", "type mismatch: expected a String but found a Number");
+}